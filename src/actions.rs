@@ -1,6 +1,6 @@
-use crate::{Coord, Health, CoordPair};
+use crate::{Coord, Health, CoordPair, CombatEffects, UnitType};
 
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum Action {
     #[default]
     Pass,
@@ -8,28 +8,123 @@ pub enum Action {
     Repair{from:Coord,to:Coord},
     Attack{from:Coord,to:Coord},
     SelfDestruct{from:Coord},
+    // reinforcement next to the AI of the player (see Game::unit_spawn)
+    Spawn{at:Coord,unit_type:UnitType},
+    // closes a turn early when playing with action points
+    EndTurn,
 }
 
+// explicit notation: "M E3-D3" (move), "A E3xD3" (attack), "R A1+B1" (repair), "S C2!" (self-destruct),
+// "N C3=Virus" (spawn), "pass" and "end" (end of turn)
 impl std::fmt::Display for Action {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", match self {
-            Self::Pass => String::from("passes"),
+            Self::Pass => String::from("pass"),
             Self::Move { from, to } => 
-                format!("move from {} to {}",from,to),
+                format!("M {}-{}",from,to),
             Self::Repair { from, to } => 
-                format!("repair from {} to {}",from,to),
+                format!("R {}+{}",from,to),
             Self::Attack { from, to } => 
-                format!("attack from {} to {}",from,to),
+                format!("A {}x{}",from,to),
             Self::SelfDestruct { from } => 
-                format!("self-destruct at {}",from),
+                format!("S {}!",from),
+            Self::Spawn { at, unit_type } => 
+                format!("N {}={}",at,unit_type),
+            Self::EndTurn => String::from("end"),
         })
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseActionError {
+    Empty,
+    // not one of M, A, R, S, N, pass or end
+    UnknownKind(String),
+    InvalidCoord(String),
+    // the separator of the kind of action is missing (e.g. '-' for a move)
+    MissingSeparator(char),
+    UnknownUnitType(String),
+    TrailingInput(String),
+}
+
+impl std::fmt::Display for ParseActionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "no action"),
+            Self::UnknownKind(kind) => write!(f, "unknown kind of action: {kind}"),
+            Self::InvalidCoord(coord) => write!(f, "invalid coordinate: {coord}"),
+            Self::MissingSeparator(separator) => write!(f, "expected '{separator}' between the coordinates"),
+            Self::UnknownUnitType(name) => write!(f, "unknown unit type: {name}"),
+            Self::TrailingInput(rest) => write!(f, "unexpected input after the action: {rest}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseActionError {}
+
+fn parse_coord(s: &str) -> Result<Coord,ParseActionError> {
+    s.parse().map_err(|_| ParseActionError::InvalidCoord(s.to_string()))
+}
+
+// the separator follows the digits of the first coordinate (rows past Z are lowercase letters, 'x' included)
+fn parse_coord_pair(s: &str, separator: char) -> Result<(Coord,Coord),ParseActionError> {
+    let row_len = s.chars().next().map_or(0, char::len_utf8);
+    let end = s[row_len..].find(|c: char| !c.is_ascii_digit()).map_or(s.len(), |index| row_len + index);
+    let (from, to) = s[end..].strip_prefix(|c: char| c.eq_ignore_ascii_case(&separator)).map(|to| (&s[..end], to))
+        .ok_or(ParseActionError::MissingSeparator(separator))?;
+    Ok((parse_coord(from)?, parse_coord(to)?))
+}
+
+// parses the notation of Display (kinds of action and unit types in any case, spaces around separators)
+impl std::str::FromStr for Action {
+    type Err = ParseActionError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (kind, rest) = s.split_once(char::is_whitespace).unwrap_or((s, ""));
+        let rest = rest.split_whitespace().collect::<String>();
+        let action = match kind.to_ascii_lowercase().as_str() {
+            "" => return Err(ParseActionError::Empty),
+            "pass" => Self::Pass,
+            "end" => Self::EndTurn,
+            "m" => parse_coord_pair(&rest, '-').map(|(from, to)| Self::Move { from, to })?,
+            "a" => parse_coord_pair(&rest, 'x').map(|(from, to)| Self::Attack { from, to })?,
+            "r" => parse_coord_pair(&rest, '+').map(|(from, to)| Self::Repair { from, to })?,
+            "s" => {
+                let from = rest.strip_suffix('!').ok_or(ParseActionError::MissingSeparator('!'))?;
+                Self::SelfDestruct { from: parse_coord(from)? }
+            },
+            "n" => {
+                let (at, name) = rest.split_once('=').ok_or(ParseActionError::MissingSeparator('='))?;
+                let unit_type = UnitType::all()
+                    .find(|t| t.to_string().eq_ignore_ascii_case(name))
+                    .ok_or(ParseActionError::UnknownUnitType(name.to_string()))?;
+                Self::Spawn { at: parse_coord(at)?, unit_type }
+            },
+            _ => return Err(ParseActionError::UnknownKind(kind.to_string())),
+        };
+        match action {
+            Self::Pass | Self::EndTurn if !rest.is_empty() => Err(ParseActionError::TrailingInput(rest)),
+            _ => Ok(action),
+        }
+    }
+}
+
 impl Action {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Pass => "pass",
+            Self::Move { .. } => "move",
+            Self::Repair { .. } => "repair",
+            Self::Attack { .. } => "attack",
+            Self::SelfDestruct { .. } => "self-destruct",
+            Self::Spawn { .. } => "spawn",
+            Self::EndTurn => "end",
+        }
+    }
+    // spawns have no source unit
     pub fn into_coord_pair(self) -> Option<CoordPair> {
         match self {
-            Self::Pass 
+            Self::Pass | Self::EndTurn | Self::Spawn { .. }
                 => None,
             Self::Move { from, to }
                 | Self::Repair { from, to }
@@ -59,8 +154,10 @@ pub enum ActionOutcome {
     Passed,
     Moved{delta:Coord},
     Repaired{amount:Health},
-    Damaged{to_source:Health,to_target:Health},
+    Damaged{to_source:Health,to_target:Health,effects:CombatEffects},
     SelfDestructed{total_damage:Health},
+    Spawned{unit_type:UnitType},
+    TurnEnded,
 }
 
 impl std::fmt::Display for ActionOutcome {
@@ -69,11 +166,15 @@ impl std::fmt::Display for ActionOutcome {
             Self::Passed => String::from("passed"),
             Self::Moved { delta } => format!("moved by {}",delta.to_string_as_tuple()),
             Self::Repaired { amount } => format!("repaired {amount} health points"),
-            Self::Damaged { to_source: 0, to_target } => 
+            Self::Damaged { to_source: 0, to_target, effects } if effects.is_empty() => 
                 format!("combat damage: {to_target}"),
-            Self::Damaged { to_source, to_target } => 
+            Self::Damaged { to_source, to_target, effects } if effects.is_empty() => 
                 format!("combat damage: to source = {to_source}, to target = {to_target}"),
+            Self::Damaged { to_source, to_target, effects } => 
+                format!("combat damage: to source = {to_source}, to target = {to_target} ({effects})"),
             Self::SelfDestructed{ total_damage } => format!("self-destructed for {total_damage} total damage"),
+            Self::Spawned { unit_type } => format!("spawned a {unit_type}"),
+            Self::TurnEnded => String::from("ended turn"),
         })
     }
 }
@@ -85,9 +186,10 @@ pub trait IsUsefulInfo {
 impl IsUsefulInfo for ActionOutcome {
     fn is_useful_info(&self) -> bool {
         matches!(self, 
-            Self::Damaged { to_source: _, to_target: _ } | 
+            Self::Damaged { .. } | 
             Self::Repaired { amount: _ } | 
-            Self::SelfDestructed { total_damage: _ }
+            Self::SelfDestructed { total_damage: _ } |
+            Self::Spawned { unit_type: _ }
         )
     }
 }
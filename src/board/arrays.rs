@@ -1,4 +1,4 @@
-use crate::{Coord, BoardCell, Dim, DEFAULT_BOARD_DIM, CoordPair, Player, BoardCellData};
+use crate::{Coord, BoardCell, Dim, DEFAULT_BOARD_DIM, CoordPair, Player, BoardCellData, Terrain};
 
 use duplicate::duplicate_item;
 
@@ -16,11 +16,13 @@ pub use vec::Board;
 impl<I> T {
     #[allow(clippy::len_without_is_empty)]
     pub const fn len(&self) -> usize {
-        let dim = self.dim as usize;
-        dim * dim
+        self.rows as usize * self.cols as usize
     }
-    pub const fn dim(&self) -> Dim {
-        self.dim
+    pub const fn rows(&self) -> Dim {
+        self.rows
+    }
+    pub const fn cols(&self) -> Dim {
+        self.cols
     }
     pub const fn inner(&self) -> &D {
         &self.data
@@ -33,7 +35,7 @@ impl<I> T {
 #[duplicate_item(I T; [const SIZE: usize] [array::BoardArray<SIZE>]; [] [vec::Board])]
 impl<I> Default for T {
     fn default() -> Self {
-        Self::new(DEFAULT_BOARD_DIM)
+        Self::new(DEFAULT_BOARD_DIM, DEFAULT_BOARD_DIM)
     }
 }
 
@@ -41,15 +43,15 @@ impl<I> Default for T {
 impl<I> T {
     const fn coord_to_index(&self, coord: Coord) -> usize {
         let (row,col) = coord.to_tuple();
-        let dim = self.dim() as usize;
+        let cols = self.cols() as usize;
         let row = row as usize;
         let col = col as usize;
-        row * dim + col
+        row * cols + col
     }
     pub fn remove(&mut self, coord: Coord) -> Option<BoardCell> {
         let index = self.coord_to_index(coord);
         let data = self.inner_mut();
-        data.get_mut(index).map(std::mem::take)
+        data.get_mut(index).map(BoardCell::take_unit)
     }
     pub fn get(&self, coord: Coord) -> Option<&BoardCell> {
         let index = self.coord_to_index(coord);
@@ -69,10 +71,16 @@ impl<I> T {
             None
         }
     }
+    // the terrain of the board position is kept
     pub fn set(&mut self, coord: Coord, value: BoardCell) {
         let index = self.coord_to_index(coord);
         let data = self.inner_mut();
-        data[index] = value;
+        data[index] = value.with_terrain(data[index].terrain());
+    }
+    pub fn set_terrain(&mut self, coord: Coord, terrain: Terrain) {
+        let index = self.coord_to_index(coord);
+        let data = self.inner_mut();
+        data[index].set_terrain(terrain);
     }
     pub fn get_two_data_mut(&mut self, coord0: Coord, coord1: Coord) -> Option<[&mut BoardCellData;2]> {
         let index0 = self.coord_to_index(coord0);
@@ -130,7 +138,7 @@ impl<I> T {
         })
     }
     pub fn rect(&self) -> CoordPair {
-        CoordPair::from_dim(self.dim())
+        CoordPair::from_dims(self.rows(), self.cols())
     }
     pub fn rect_iter(&self) -> impl Iterator<Item = Coord> {
         self.rect().rect_iter()
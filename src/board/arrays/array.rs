@@ -8,18 +8,20 @@ pub type BoardData<const SIZE: usize> = [BoardCell;SIZE];
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct BoardArray<const SIZE: usize> {
     pub (super) data: BoardData<SIZE>,
-    pub (super) dim: Dim,
+    pub (super) rows: Dim,
+    pub (super) cols: Dim,
 }
 
 impl<const SIZE: usize> BoardArray<SIZE> {
     pub const fn size(&self) -> usize {
         SIZE
     }
-    pub fn new(dim: Dim) -> Self {
-        assert!(dim > 0);
-        assert!(dim as usize*dim as usize <= SIZE,"{}x{} board will not fit in array of size {}",dim,dim,SIZE);
+    pub fn new(rows: Dim, cols: Dim) -> Self {
+        assert!(rows > 0 && cols > 0);
+        assert!(rows as usize*cols as usize <= SIZE,"{}x{} board will not fit in array of size {}",rows,cols,SIZE);
         Self {
-            dim,
+            rows,
+            cols,
             data : [Default::default();SIZE],
         }
     }
@@ -5,21 +5,21 @@ pub type BoardData = Vec<BoardCell>;
 #[derive(Debug, PartialEq, Clone)]
 pub struct Board {
     pub (super) data: BoardData,
-    pub (super) dim: Dim,
+    pub (super) rows: Dim,
+    pub (super) cols: Dim,
 }
 
 impl Board {
     pub fn size(&self) -> usize {
         self.data.capacity()
     }
-    pub fn new(dim: Dim) -> Self {
-        assert!(dim > 0);
-        let dimu = dim as usize;
-        let cap = dimu * dimu;
+    pub fn new(rows: Dim, cols: Dim) -> Self {
+        assert!(rows > 0 && cols > 0);
+        let cap = rows as usize * cols as usize;
         let mut data = Vec::with_capacity(cap);
         for _ in 0..cap {
             data.push(Default::default());
         }
-        Self { dim, data }
+        Self { rows, cols, data }
     }
 }
@@ -1,22 +1,23 @@
 use std::collections::HashMap;
 
-use crate::{Coord, BoardCell, Dim, DEFAULT_BOARD_DIM, Player, CoordPair, BoardCellData};
+use crate::{Coord, BoardCell, Dim, DEFAULT_BOARD_DIM, Player, CoordPair, BoardCellData, Terrain};
 
 type BoardData = HashMap<Coord,BoardCell>;
 
 #[derive(Debug, Clone)]
 pub struct Board {
     data: BoardData,
-    dim: Dim,
+    rows: Dim,
+    cols: Dim,
     empty_cell: BoardCell, 
 }
 
 impl Board {
-    pub fn new(dim: Dim) -> Self {
-        assert!(dim > 0);
+    pub fn new(rows: Dim, cols: Dim) -> Self {
+        assert!(rows > 0 && cols > 0);
         let data = HashMap::new();
         let empty_cell = BoardCell::new();
-        Self { dim, data, empty_cell }
+        Self { rows, cols, data, empty_cell }
     }
 }
 
@@ -26,32 +27,35 @@ impl Board {
     }
     #[allow(clippy::len_without_is_empty)]
     pub const fn len(&self) -> usize {
-        let dim = self.dim as usize;
-        dim * dim
+        self.rows as usize * self.cols as usize
     }
-    pub const fn dim(&self) -> Dim {
-        self.dim
+    pub const fn rows(&self) -> Dim {
+        self.rows
+    }
+    pub const fn cols(&self) -> Dim {
+        self.cols
     }
 }
 
 impl Default for Board {
     fn default() -> Self {
-        Self::new(DEFAULT_BOARD_DIM)
+        Self::new(DEFAULT_BOARD_DIM, DEFAULT_BOARD_DIM)
     }
 }
 
 impl Board {
     fn is_valid_position(&self, coord : Coord) -> bool {
         let (row,col) = coord.to_tuple();
-        let is_valid = row >= 0 && col >= 0 && row < self.dim && col < self.dim;
-        debug_assert!(is_valid,"({},{}) is not valid for a {}x{} board",row,col,self.dim,self.dim);
-        is_valid
+        row >= 0 && col >= 0 && row < self.rows && col < self.cols
     }
     pub fn remove(&mut self, coord: Coord) -> Option<BoardCell> {
         if self.is_valid_position(coord) {
             if let Some(cell_ref) = self.data.get_mut(&coord) {
-                let old_cell = std::mem::take(cell_ref);
-                self.data.remove(&coord);
+                let old_cell = cell_ref.take_unit();
+                // cells are only kept for their terrain
+                if old_cell.terrain() == Terrain::Plain {
+                    self.data.remove(&coord);
+                }
                 Some(old_cell)
             } else {
                 Some(BoardCell::new())
@@ -63,11 +67,7 @@ impl Board {
     pub fn get(&self, coord: Coord) -> Option<&BoardCell> {
         if self.is_valid_position(coord) {
             if let Some(cell_ref) = self.data.get(&coord) {
-                if cell_ref.is_empty() {
-                    Some(&self.empty_cell)
-                } else {
-                    Some(cell_ref)
-                }
+                Some(cell_ref)
             } else {
                 Some(&self.empty_cell)
             }
@@ -82,9 +82,23 @@ impl Board {
             None
         }
     }
+    // the terrain of the board position is kept
     pub fn set(&mut self, coord: Coord, value: BoardCell) {
         if !value.is_empty() {
-            self.data.insert(coord, value);
+            let terrain = self.data.get(&coord).map(BoardCell::terrain).unwrap_or_default();
+            self.data.insert(coord, value.with_terrain(terrain));
+        } else {
+            self.remove(coord);
+        }
+    }
+    pub fn set_terrain(&mut self, coord: Coord, terrain: Terrain) {
+        if let Some(cell_ref) = self.data.get_mut(&coord) {
+            cell_ref.set_terrain(terrain);
+            if cell_ref.is_empty() && terrain == Terrain::Plain {
+                self.data.remove(&coord);
+            }
+        } else if terrain != Terrain::Plain {
+            self.data.insert(coord, BoardCell::new_terrain(terrain));
         }
     }
     pub fn get_two_data_mut(&mut self, coord0: Coord, coord1: Coord) -> Option<[&mut BoardCellData;2]> {
@@ -109,8 +123,8 @@ impl Board {
         }
         Some([ref_mut_0, ref_mut_1])
     }
-    pub fn iter_units(&self) -> std::collections::hash_map::Values<Coord, BoardCell> {
-        self.data.values()
+    pub fn iter_units(&self) -> impl Iterator<Item=&BoardCell> + '_ {
+        self.data.values().filter(|cell| cell.is_unit())
     }
     pub fn iter_player_units(&self, player: Player) -> impl Iterator<Item=&BoardCell> + '_ {
         self.data.values().filter(move|cell|{
@@ -136,7 +150,7 @@ impl Board {
         })
     }
     pub fn rect(&self) -> CoordPair {
-        CoordPair::from_dim(self.dim())
+        CoordPair::from_dims(self.rows(), self.cols())
     }
     pub fn rect_iter(&self) -> impl Iterator<Item = Coord> {
         self.rect().rect_iter()
@@ -146,7 +160,7 @@ impl Board {
     }
     pub fn player_coords(&self, player: Player) -> impl Iterator<Item = Coord> + '_ {
         self.data.iter().filter_map(move|(&coord,cell)|
-            if cell.player().expect("should be a unit") == player { Some(coord) } else { None }
+            if cell.player() == Some(player) { Some(coord) } else { None }
         )
     }
 }
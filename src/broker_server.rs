@@ -0,0 +1,102 @@
+use crate::{http::{self, Request, Response}, protocol::{BrokerData, BrokerReply}};
+
+use std::collections::HashMap;
+use std::io::Result as IoResult;
+use std::net::TcpListener;
+use std::sync::Mutex;
+
+// game broker for the broker client of the console (see Game::broker_post_move and Game::broker_get_move):
+// every URL path is a game channel ("/test" for --broker http://localhost:8001/test)
+//   GET  /CHANNEL          latest move of the channel (no data before the first move)
+//   GET  /CHANNEL?turn=N   move N of the channel (no data if not played yet)
+//   POST /CHANNEL          next move ({"version":2,"action":"move","from":..,"to":..,"turn":N} or version 1
+//                          {"from":..,"to":..,"turn":N}): N follows the latest turn, the latest move again
+//                          is accepted as a retry, and turn 1 starts a new game
+//   POST /CHANNEL          game result after the latest move ({"version":2,"turn":N,"result":"attacker"}),
+//                          kept with that move (every client may report the same result)
+// errors are replied with a 404 status, as the client expects
+#[derive(Debug, Default)]
+pub struct BrokerServer {
+    // moves of each channel by turn (turn N at index N-1)
+    channels: Mutex<HashMap<String,Vec<BrokerData>>>,
+}
+
+fn reply(data: Option<BrokerData>) -> Response {
+    let reply = BrokerReply { success: true, error: None, data };
+    Response::new(200, "application/json", serde_json::to_string(&reply).expect("serializable reply"))
+}
+
+fn failure(error: impl Into<String>) -> Response {
+    let reply = BrokerReply { success: false, error: Some(error.into()), data: None };
+    Response::new(404, "application/json", serde_json::to_string(&reply).expect("serializable reply"))
+}
+
+impl BrokerServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    // moves posted to a channel so far
+    pub fn moves(&self, channel: &str) -> Vec<BrokerData> {
+        self.channels.lock().expect("should get a lock").get(channel).cloned().unwrap_or_default()
+    }
+    fn get(&self, channel: &str, turn: Option<&str>) -> Response {
+        let channels = self.channels.lock().expect("should get a lock");
+        let moves = channels.get(channel).map(Vec::as_slice).unwrap_or_default();
+        match turn.map(str::parse::<usize>) {
+            None => reply(moves.last().copied()),
+            Some(Ok(turn)) if turn > 0 => reply(moves.get(turn-1).copied()),
+            Some(_) => failure(format!("invalid turn: {}", turn.unwrap_or_default())),
+        }
+    }
+    fn post(&self, channel: &str, body: &str) -> Response {
+        let data = match serde_json::from_str::<BrokerData>(body) {
+            Ok(data) => data,
+            Err(error) => return failure(format!("invalid move data: {error}")),
+        };
+        let mut channels = self.channels.lock().expect("should get a lock");
+        let moves = channels.entry(channel.to_string()).or_default();
+        if data.is_result() {
+            return match moves.last_mut() {
+                Some(last) if last.turn == data.turn && last.result.is_some() && last.result != data.result =>
+                    failure(format!("another result was reported for turn {}", data.turn)),
+                Some(last) if last.turn == data.turn => {
+                    last.result = data.result;
+                    reply(Some(*last))
+                },
+                _ => failure(format!("no move for a result at turn {}", data.turn)),
+            };
+        }
+        // a move already stored is a retry (the reply was lost), even a turn 1 while the game goes on:
+        // only another turn 1, or any turn 1 once the game is over, starts a new game
+        let over = moves.last().is_some_and(|last| last.result.is_some());
+        let stored = data.turn.checked_sub(1).and_then(|index| moves.get(index));
+        if stored.is_some_and(|stored| BrokerData { result: None, ..*stored } == data) && !(data.turn == 1 && over) {
+            return reply(Some(data));
+        }
+        if data.turn == 1 {
+            moves.clear();
+        }
+        let expected = moves.len() + 1;
+        if data.turn == expected {
+            moves.push(data);
+            reply(Some(data))
+        } else {
+            failure(format!("expected turn {expected}, got turn {}", data.turn))
+        }
+    }
+    pub fn handle(&self, request: &Request) -> Response {
+        let channel = request.path.trim_matches('/');
+        if channel.is_empty() {
+            return failure("missing game channel in the url (e.g. /test)");
+        }
+        match request.method.as_str() {
+            "GET" => self.get(channel, request.query("turn")),
+            "POST" => self.post(channel, &request.body),
+            method => failure(format!("method not allowed: {method}")),
+        }
+    }
+    // answers requests until the listener fails (bind to port 0 for any free port)
+    pub fn serve(self, listener: TcpListener) -> IoResult<()> {
+        http::serve(listener, move |request| self.handle(request))
+    }
+}
@@ -1,12 +1,15 @@
-use crate::{UnitType, Player, Unit, DisplayFirstLetter};
+use crate::{UnitType, Player, Unit, DisplayFirstLetter, Health, MAX_HEALTH, Terrain, Effects};
 
-#[derive(Debug, PartialEq, Clone, Copy, Default)]
-#[repr(transparent)]
+use anyhow::anyhow;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
 pub struct BoardCell {
     data: Option<BoardCellData>,
+    // terrain stays with the board position (see Board::set and Board::remove)
+    terrain: Terrain,
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum BoardCellData {
     Unit { player:Player, unit:Unit },
 }
@@ -30,18 +33,45 @@ impl BoardCell {
         // empty cell
         Self::default()
     }
+    pub fn new_terrain(terrain: Terrain) -> Self {
+        Self { data: None, terrain }
+    }
     pub fn new_unit(player: Player, unit_type: UnitType) -> Self {
         Self { data : Some(
             BoardCellData::Unit { 
                 player,
                 unit: Unit::new(unit_type),
             }
-        )}
+        ), terrain: Default::default() }
+    }
+    pub fn new_unit_with_health(player: Player, unit_type: UnitType, health: Health) -> Self {
+        Self { data : Some(
+            BoardCellData::Unit { 
+                player,
+                unit: Unit::with_health(unit_type, health),
+            }
+        ), terrain: Default::default() }
+    }
+    pub fn with_terrain(self, terrain: Terrain) -> Self {
+        Self { terrain, ..self }
+    }
+    pub fn terrain(&self) -> Terrain {
+        self.terrain
+    }
+    pub fn set_terrain(&mut self, terrain: Terrain) {
+        self.terrain = terrain;
     }
     pub fn is_empty(&self) -> bool {
-        // None means Empty cell
+        // None means no unit (the cell may still have terrain)
         self.data.is_none()
     }
+    pub fn is_passable(&self) -> bool {
+        self.is_empty() && self.terrain.is_passable()
+    }
+    // takes the unit out of the cell, leaving the terrain behind
+    pub fn take_unit(&mut self) -> Self {
+        Self { data: self.data.take(), terrain: self.terrain }
+    }
     pub fn is_unit(&self) -> bool {
         matches!(self.data, Some(BoardCellData::Unit { player: _, unit: _ }))
     }
@@ -82,19 +112,29 @@ impl BoardCell {
             false
         }
     }
+    // unit only (the terrain marker is printed as the separator before the cell)
     pub fn to_pretty_compact_string(&self) -> String {
-        if self.is_empty() {
+        if self.terrain == Terrain::Wall {
+            String::from("###")
+        } else if self.is_empty() {
             String::from(" . ")
         } else {
-            self.to_compact_string()
+            self.unit_compact_string()
         }
     }
+    // terrain marker followed by the unit
     pub fn to_compact_string(&self) -> String {
+        match self.terrain.marker() {
+            Some(marker) => format!("{marker}{}", self.unit_compact_string()),
+            None => self.unit_compact_string(),
+        }
+    }
+    fn unit_compact_string(&self) -> String {
         match self.data {
             None => String::from(""),
-            Some(BoardCellData::Unit { player, unit }) => format!("{}{}{:1}",
+            Some(BoardCellData::Unit { player, unit }) => format!("{}{}{:1}{}",
                 player.to_first_letter().to_ascii_lowercase(),
-                unit.unit_type.to_first_letter().to_ascii_uppercase(),unit.health),
+                unit.unit_type.to_first_letter().to_ascii_uppercase(),unit.health,unit.effects),
         }
     }
     pub fn data(&mut self) -> Option<&BoardCellData> {
@@ -117,3 +157,42 @@ impl std::fmt::Display for BoardCell {
     }
 }
 
+impl std::str::FromStr for BoardCell {
+    type Err = anyhow::Error;
+    // parses the output of to_compact_string (empty string is an empty cell)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let terrain = s.chars().next().and_then(Terrain::from_marker).unwrap_or_default();
+        let s = if terrain == Terrain::Plain { s } else { &s[1..] };
+        if s.is_empty() {
+            return Ok(Self::new_terrain(terrain));
+        }
+        if !terrain.is_passable() {
+            return Err(anyhow!("unit on impassable terrain: {s}"));
+        }
+        let mut chars = s.chars();
+        let (Some(player_char), Some(unit_char)) = (chars.next(), chars.next()) else {
+            return Err(anyhow!("invalid cell: {s}"));
+        };
+        let player = Player::all()
+            .find(|p| p.to_first_letter().to_ascii_lowercase() == player_char)
+            .ok_or(anyhow!("invalid player in cell: {s}"))?;
+        let unit_type = UnitType::all()
+            .find(|t| t.to_first_letter().to_ascii_uppercase() == unit_char)
+            .ok_or(anyhow!("invalid unit type in cell: {s}"))?;
+        // health followed by the status effects
+        let rest = chars.as_str();
+        let (health, effects) = rest.split_at(rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len()));
+        let health = health.parse::<Health>()
+            .map_err(|_| anyhow!("invalid health in cell: {s}"))?;
+        if health > MAX_HEALTH {
+            return Err(anyhow!("health too high in cell: {s}"));
+        }
+        let mut cell = Self::new_unit_with_health(player, unit_type, health).with_terrain(terrain);
+        if let Some(unit) = cell.unit_mut() {
+            unit.effects = effects.parse::<Effects>()?;
+        }
+        Ok(cell)
+    }
+}
+
@@ -77,6 +77,21 @@ impl std::fmt::Display for Coord {
     }
 }
 
+// letter-number notation (as in Display): row letter (A-Z then a-z) followed by column number
+impl std::str::FromStr for Coord {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.trim().chars();
+        let row = match chars.next() {
+            Some(c @ 'A'..='Z') => c as u8 - b'A',
+            Some(c @ 'a'..='z') => c as u8 - b'a' + 26,
+            _ => return Err(anyhow::anyhow!("invalid coordinate: {s}")),
+        };
+        let col = chars.as_str().parse::<Dim>().map_err(|_| anyhow::anyhow!("invalid coordinate: {s}"))?;
+        Ok(Self::new(row as Dim, col))
+    }
+}
+
 impl From<CoordTuple> for Coord {
     fn from(coord: CoordTuple) -> Self {
         Self::from_tuple(coord)
@@ -131,8 +146,11 @@ impl CoordPair {
         Self {from,to}
     }
     pub fn from_dim(dim: Dim) -> Self {
+        Self::from_dims(dim, dim)
+    }
+    pub fn from_dims(rows: Dim, cols: Dim) -> Self {
         let from = Coord::new(0, 0);
-        let to = Coord::new(dim-1,dim-1);
+        let to = Coord::new(rows-1,cols-1);
         Self {from,to}
     }
     pub fn rect_iter(self) -> impl Iterator<Item = Coord> {
@@ -0,0 +1,130 @@
+use crate::{UnitType, DisplayFirstLetter};
+
+use anyhow::anyhow;
+
+// status effects of a unit, counted in turns of the unit's player (see Game::next_turn)
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Effects {
+    // type of the unit that infected it (which gives the damage per turn) and turns left
+    pub (crate) infection: Option<(UnitType, u8)>,
+    // the unit can't move while stunned
+    pub (crate) stun: u8,
+    // turns before the shield comes back after absorbing a hit (units with a shield start with it up)
+    pub (crate) shield_recharge: u8,
+}
+
+impl Effects {
+    pub fn infection(&self) -> Option<(UnitType, u8)> {
+        self.infection
+    }
+    pub fn stun(&self) -> u8 {
+        self.stun
+    }
+    pub fn shield_recharge(&self) -> u8 {
+        self.shield_recharge
+    }
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+// effects applied to a unit during a combat (see ActionOutcome::Damaged)
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct InflictedEffects {
+    // its shield absorbed the hit
+    pub absorbed: bool,
+    pub infection: u8,
+    pub stun: u8,
+}
+
+impl InflictedEffects {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl std::fmt::Display for InflictedEffects {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut effects = Vec::new();
+        if self.absorbed {
+            effects.push(String::from("shield absorbed the hit"));
+        }
+        if self.infection > 0 {
+            effects.push(format!("infected for {} turns", self.infection));
+        }
+        if self.stun > 0 {
+            effects.push(format!("stunned for {} turns", self.stun));
+        }
+        write!(f, "{}", effects.join(", "))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct CombatEffects {
+    pub source: InflictedEffects,
+    pub target: InflictedEffects,
+}
+
+impl CombatEffects {
+    pub fn is_empty(&self) -> bool {
+        self.source.is_empty() && self.target.is_empty()
+    }
+}
+
+impl std::fmt::Display for CombatEffects {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sides = [("source", &self.source), ("target", &self.target)].into_iter()
+            .filter(|(_, effects)| !effects.is_empty())
+            .map(|(side, effects)| format!("{side} {effects}"))
+            .collect::<Vec<_>>();
+        write!(f, "{}", sides.join("; "))
+    }
+}
+
+// suffix of the cell in position strings: "iV2" (infected by a Virus for 2 turns),
+// "s1" (stunned for 1 turn) and "r3" (shield back in 3 turns)
+impl std::fmt::Display for Effects {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some((source, turns)) = self.infection {
+            write!(f, "i{}{turns}", source.to_first_letter().to_ascii_uppercase())?;
+        }
+        if self.stun > 0 {
+            write!(f, "s{}", self.stun)?;
+        }
+        if self.shield_recharge > 0 {
+            write!(f, "r{}", self.shield_recharge)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Effects {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut effects = Self::default();
+        let mut rest = s;
+        while let Some(marker) = rest.chars().next() {
+            rest = &rest[1..];
+            let source = if marker == 'i' {
+                let letter = rest.chars().next().ok_or(anyhow!("missing infection source: {s}"))?;
+                rest = &rest[letter.len_utf8()..];
+                Some(UnitType::all()
+                    .find(|t| t.to_first_letter().to_ascii_uppercase() == letter)
+                    .ok_or(anyhow!("invalid infection source: {s}"))?)
+            } else {
+                None
+            };
+            let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            let turns = rest[..digits].parse::<u8>().ok().filter(|&turns| turns > 0)
+                .ok_or(anyhow!("invalid effect turns: {s}"))?;
+            rest = &rest[digits..];
+            match (marker, source) {
+                ('i', Some(source)) => effects.infection = Some((source, turns)),
+                ('s', _) => effects.stun = turns,
+                ('r', _) => effects.shield_recharge = turns,
+                _ => return Err(anyhow!("unknown effect: {marker}")),
+            }
+        }
+        Ok(effects)
+    }
+}
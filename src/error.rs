@@ -0,0 +1,57 @@
+use crate::{game::IllegalActionReason, ParseActionError};
+
+// errors of the public API of Game (actions, parsing, records and the game broker)
+#[derive(Debug, thiserror::Error)]
+pub enum WargameError {
+    // the action breaks the rules
+    #[error(transparent)]
+    Rules(#[from] IllegalActionReason),
+    #[error(transparent)]
+    Parse(#[from] ParseActionError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Broker(#[from] BrokerError),
+    #[error("the game is over")]
+    GameOver,
+    // the scenario doesn't fit the rules (see Scenario::validate)
+    #[error("{0}")]
+    Scenario(String),
+    // a line of a game record (see Game::replay)
+    #[error("line {line}: {error}")]
+    Record { line: usize, error: Box<WargameError> },
+}
+
+// failures to reach the game broker or to understand its replies
+#[derive(Debug, thiserror::Error)]
+pub enum BrokerError {
+    #[error("no broker")]
+    NoBroker,
+    #[error("broker request failed: {0}")]
+    Request(String),
+    #[error("broker request timed out: {0}")]
+    Timeout(String),
+    #[error("broker error: http status {0}")]
+    Status(u16),
+    #[error("invalid broker data: {0}")]
+    Data(String),
+    // error message of the broker
+    #[error("broker error: {0}")]
+    Reply(String),
+    #[error("broker error: unknown")]
+    Unknown,
+    // the broker and the game don't agree on the turn (see Game::broker_resync)
+    #[error("broker out of sync: expected turn {expected}, got turn {got}")]
+    Desync { expected: usize, got: usize },
+}
+
+impl BrokerError {
+    // failures worth retrying: the broker may answer later
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Request(_) | Self::Timeout(_) => true,
+            Self::Status(status) => *status >= 500,
+            _ => false,
+        }
+    }
+}
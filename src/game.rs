@@ -1,4 +1,4 @@
-use crate::{Coord, UnitType, BoardCell, Dim, Player, Board, DisplayFirstLetter, Action, ActionOutcome, CoordPair, BoardCellData, HeuristicScore, DEFAULT_MAX_DEPTH, DEFAULT_BOARD_DIM, heuristics::{self, MIN_HEURISTIC_SCORE, MAX_HEURISTIC_SCORE}, Heuristics, DEFAULT_MIN_DEPTH, IsUsefulInfo, DEFAULT_MAX_MOVES, DEFAULT_MAX_SECONDS};
+use crate::{Coord, RuleSet, rules::LineOfSight, Scenario, Terrain, Health, scenario::parse_dims, BoardCell, Dim, Player, Board, DisplayFirstLetter, Action, ActionOutcome, CombatEffects, CoordPair, BoardCellData, HeuristicScore, DEFAULT_MAX_DEPTH, DEFAULT_BOARD_DIM, heuristics::{self, MIN_HEURISTIC_SCORE, MAX_HEURISTIC_SCORE}, Heuristics, DEFAULT_MIN_DEPTH, IsUsefulInfo, DEFAULT_MAX_MOVES, DEFAULT_MAX_SECONDS, WargameError};
 
 #[cfg(feature="stats")]
 use crate::{number_digits_precision_to_string, rescale_number_to_string};
@@ -22,9 +22,31 @@ use rayon::prelude::*;
 
 pub mod console;
 pub mod web;
+pub mod features;
+pub mod totals;
+pub mod fog;
+pub mod repetition;
+pub mod victory;
+pub mod teams;
+pub mod turns;
+pub mod reinforcements;
+pub mod record;
+pub mod legality;
+
+pub use totals::UnitTotals;
+pub use fog::Observation;
+pub use repetition::RepetitionRule;
+pub use victory::VictoryConditions;
+pub use teams::Teams;
+pub use turns::{ActionPoints, TurnPhase};
+pub use reinforcements::{Reinforcements, Reserve};
+pub use legality::IllegalActionReason;
+pub use console::ConsoleCommand;
 
 #[cfg(feature="broker")]
 pub mod broker;
+#[cfg(feature="tui")]
+pub mod tui;
 
 #[derive(Debug, Clone)]
 pub struct Game {
@@ -39,26 +61,39 @@ pub struct GameState {
     player: Player,
     board: Board,
     total_moves: usize,
+    // actions played so far (more than the moves with action points)
+    total_actions: usize,
     deadlock : bool,
-    attacker_has_ai: bool,
-    defender_has_ai: bool,
+    totals: UnitTotals,
+    initial_totals: UnitTotals,
+    // terrain never changes during a game so the depots are shared
+    depots: Arc<Vec<Coord>>,
+    history: Option<Arc<repetition::PositionHistory>>,
+    phase: TurnPhase,
+    // reinforcements left to each player
+    reserves: [Reserve;Player::cardinality()],
 }
 
 impl GameState {
-    fn new(dim: Dim) -> Self {
+    fn new(rows: Dim, cols: Dim) -> Self {
         Self {
             player: Default::default(),
-            board: Board::new(dim),
+            board: Board::new(rows, cols),
             total_moves: 0,
+            total_actions: 0,
             deadlock: false,
-            attacker_has_ai: false,
-            defender_has_ai: false,
+            totals: Default::default(),
+            initial_totals: Default::default(),
+            depots: Default::default(),
+            history: None,
+            phase: Default::default(),
+            reserves: Default::default(),
         }
     }
 }
 impl Default for GameState {
     fn default() -> Self {
-        Self::new(DEFAULT_BOARD_DIM)
+        Self::new(DEFAULT_BOARD_DIM, DEFAULT_BOARD_DIM)
     }
 }
 
@@ -67,10 +102,39 @@ impl GameState {
         Self {
             player: self.player,
             total_moves: self.total_moves,
+            total_actions: self.total_actions,
             board: self.board,
             deadlock: self.deadlock,
-            attacker_has_ai: self.attacker_has_ai,
-            defender_has_ai: self.defender_has_ai,
+            totals: self.totals,
+            initial_totals: self.initial_totals,
+            depots: self.depots,
+            history: self.history,
+            phase: self.phase,
+            reserves: self.reserves,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameResult {
+    Winner(Player),
+    Draw,
+}
+
+impl GameResult {
+    pub fn winner(&self) -> Option<Player> {
+        match self {
+            Self::Winner(player) => Some(*player),
+            Self::Draw => None,
+        }
+    }
+}
+
+impl std::fmt::Display for GameResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Winner(player) => write!(f, "{player}"),
+            Self::Draw => write!(f, "Draw"),
         }
     }
 }
@@ -88,7 +152,13 @@ pub struct GameStats {
 #[derive(Debug, Clone, SmartDefault)]
 pub struct GameOptions {
     #[default(DEFAULT_BOARD_DIM)]
-    pub dim: Dim,
+    pub rows: Dim,
+    #[default(DEFAULT_BOARD_DIM)]
+    pub cols: Dim,
+    // players of the classic setup (replaced by the players of the scenario)
+    #[default(vec![Player::Attacker, Player::Defender])]
+    pub players: Vec<Player>,
+    pub teams: Teams,
     #[default(Some(DEFAULT_MAX_DEPTH))]
     pub max_depth: Option<usize>,
     #[default(Some(DEFAULT_MIN_DEPTH))]
@@ -115,6 +185,29 @@ pub struct GameOptions {
     #[default(1)]
     pub parallel_levels : usize,
     pub broker : Option<String>,
+    // broker client: timeout of each request, delay between two polls or retries (doubled from the
+    // minimum up to the maximum) and failed requests in a row before giving up
+    #[default(5.0)]
+    pub broker_timeout : f32,
+    #[default(0.1)]
+    pub broker_min_delay : f32,
+    #[default(2.0)]
+    pub broker_max_delay : f32,
+    #[default(5)]
+    pub broker_max_failures : usize,
+    pub rules : RuleSet,
+    pub scenario : Option<Scenario>,
+    // players only see units within this distance of their own units
+    pub fog_radius : Option<Dim>,
+    // number of determinized games searched when playing under fog of war
+    #[default(8)]
+    pub fog_samples : usize,
+    pub repetition : RepetitionRule,
+    pub victory : VictoryConditions,
+    // several actions per turn (one action per turn if None)
+    pub action_points : Option<ActionPoints>,
+    // AIs can spawn new units (no reinforcements if None)
+    pub reinforcements : Option<Reinforcements>,
 }
 
 impl Default for Game {
@@ -124,40 +217,42 @@ impl Default for Game {
 }
 
 impl Game {
-    pub fn new(options: GameOptions) -> Self 
-    {
-        let dim = options.dim;
-        let mut game = Self {
-            state: GameState::new(dim),
+    fn new_empty(options: GameOptions) -> Self {
+        let mut state = GameState::new(options.rows, options.cols);
+        let spawns = options.reinforcements.map_or(0, |reinforcements| reinforcements.spawns);
+        state.reserves = [Reserve { spawns, cooldown: 0 };Player::cardinality()];
+        Self {
+            state,
             options: Arc::new(options),
             #[cfg(feature="stats")]
             stats: Default::default(),
-        };
-        assert!(dim >= 4,"initial setup requires minimum of 4x4 board");
-        use UnitType::*;
-        let init_p1 = vec![
-            (0,0,AI),(0,1,Virus),(0,2,Program),
-            (1,0,Virus),(1,1,Firewall),
-            (2,0,Program),
-        ];
-        let init_p2 = vec![
-            (0,0,AI),(0,1,Tech),(0,2,Firewall),
-            (1,0,Tech),(1,1,Program),
-            (2,0,Firewall),
-        ];
-        assert_eq!(Player::cardinality(),2);
-        let mut p_all = Player::all();
-        let p1 = p_all.next().unwrap();
-        let p2 = p_all.next().unwrap();
-        for (row,col,unit_type) in init_p1 {
-            game.set_cell((dim-1-row,dim-1-col),BoardCell::new_unit(p1, unit_type));
-        }
-        for (row,col,unit_type) in init_p2 {
-            game.set_cell((row,col),BoardCell::new_unit(p2, unit_type));
-        }
-        game.state.attacker_has_ai = true;
-        game.state.defender_has_ai = true;
-        game
+        }
+    }
+    // panics if the scenario doesn't fit the rules (see Game::try_new)
+    pub fn new(options: GameOptions) -> Self 
+    {
+        Self::try_new(options).expect("scenario should be valid for the rules")
+    }
+    // fails if the scenario doesn't fit the rules (see Scenario::validate)
+    pub fn try_new(options: GameOptions) -> Result<Self,WargameError> {
+        let mut options = options;
+        let scenario = options.scenario.clone()
+            .unwrap_or_else(|| Scenario::classic_for(options.rows, options.cols, &options.players));
+        scenario.validate(&options.rules).map_err(|error| WargameError::Scenario(error.to_string()))?;
+        (options.rows, options.cols) = (scenario.rows, scenario.cols);
+        options.players = scenario.players.clone();
+        let mut game = Self::new_empty(options);
+        game.state.player = scenario.first_player;
+        for &(coord, terrain) in &scenario.terrain {
+            game.set_terrain(coord, terrain);
+        }
+        for unit in &scenario.units {
+            let health = unit.health.unwrap_or(game.rules().initial_health(unit.unit_type));
+            game.set_cell(unit.coord, BoardCell::new_unit_with_health(unit.player, unit.unit_type, health));
+        }
+        game.state.initial_totals = game.state.totals;
+        game.record_position(false);
+        Ok(game)
     }
     pub fn into_shallow_copy(self) -> Self {
         Self {
@@ -167,8 +262,11 @@ impl Game {
             stats: self.stats,
         }
     }
-    pub fn dim(&self) -> Dim {
-        self.options.dim
+    pub fn rows(&self) -> Dim {
+        self.options.rows
+    }
+    pub fn cols(&self) -> Dim {
+        self.options.cols
     }
     #[cfg(feature="stats")]
     pub fn stats(&self) -> Arc<Mutex<GameStats>> {
@@ -183,6 +281,9 @@ impl Game {
         let mut stats = self.stats.lock().expect("lock should work");
         *stats = Default::default();
     }
+    pub fn rules(&self) -> &RuleSet {
+        &self.options.rules
+    }
     pub fn options(&self) -> Arc<GameOptions> {
         self.options.clone()
     }
@@ -197,7 +298,11 @@ impl Game {
     }
     pub fn remove_cell(&mut self, coord: Coord) -> Option<BoardCell> {
         if self.is_valid_position(coord) {
-            self.state.board.remove(coord)
+            let removed = self.state.board.remove(coord);
+            if let Some(cell) = &removed {
+                self.state.totals.remove_cell(coord, cell);
+            }
+            removed
         } else {
             None
         }
@@ -219,9 +324,33 @@ impl Game {
     pub fn set_cell(&mut self, coord: impl Into<Coord>, value: BoardCell) {
         let coord = coord.into();
         if self.is_valid_position(coord) {
+            let old = *self.state.board.get(coord).unwrap();
+            self.state.totals.remove_cell(coord, &old);
+            self.state.totals.add_cell(coord, &value);
             self.state.board.set(coord,value);
         }
     }
+    pub fn terrain(&self, coord: Coord) -> Terrain {
+        self.get_cell(coord).map(BoardCell::terrain).unwrap_or_default()
+    }
+    // a unit standing where a wall is placed is removed
+    pub fn set_terrain(&mut self, coord: Coord, terrain: Terrain) {
+        if self.is_valid_position(coord) {
+            if !terrain.is_passable() {
+                self.remove_cell(coord);
+            }
+            self.state.board.set_terrain(coord, terrain);
+            let repair = self.rules().terrain_repair(terrain);
+            let depots = Arc::make_mut(&mut self.state.depots);
+            depots.retain(|&depot| depot != coord);
+            if repair > 0 {
+                depots.push(coord);
+            }
+        }
+    }
+    pub fn damage_reduction(&self, coord: Coord) -> Health {
+        self.rules().damage_reduction(self.terrain(coord))
+    }
     pub fn get_two_cell_data_mut(&mut self, coord0: Coord, coord1: Coord) -> Option<[&mut BoardCellData;2]> {
         if self.is_valid_position(coord0) &&
             self.is_valid_position(coord1) &&
@@ -238,36 +367,82 @@ impl Game {
     pub fn total_moves(&self) -> usize {
         self.state.total_moves
     }
+    pub fn total_actions(&self) -> usize {
+        self.state.total_actions
+    }
+    pub fn unit_totals(&self) -> &UnitTotals {
+        &self.state.totals
+    }
+    pub fn initial_unit_totals(&self) -> &UnitTotals {
+        &self.state.initial_totals
+    }
+    pub fn recount_unit_totals(&self) -> UnitTotals {
+        UnitTotals::from_unit_coords(self.unit_coords())
+    }
     pub fn next_turn(&mut self) -> Player {
-        self.state.player = self.state.player.next();
+        self.tick_effects();
+        self.state.player = self.next_active_player(self.state.player);
         self.state.total_moves += 1;
+        self.state.phase = Default::default();
+        self.terrain_repair();
+        self.reserve_tick();
         self.state.player
     }
     pub fn into_next_turn(self) -> Self {
         let mut next = self.into_shallow_copy();
-        next.state.player = next.state.player.next();
-        next.state.total_moves += 1;
+        next.next_turn();
         next
     }
+    // units of the player about to play are repaired by the terrain they stand on (depots)
+    fn terrain_repair(&mut self) {
+        let (player, depots, options) = (self.player(), self.state.depots.clone(), self.options.clone());
+        for &coord in depots.iter() {
+            let repair = options.rules.terrain_repair(self.terrain(coord));
+            if let Some((unit_player, unit)) = self.get_cell_data_mut(coord).and_then(BoardCellData::player_unit_mut) {
+                if *unit_player == player {
+                    let max_health = unit.initial_health(&options.rules);
+                    let health = unit.health;
+                    unit.health = max_health.min(health.saturating_add(repair));
+                    let (unit_type, amount) = (unit.unit_type, unit.health - health);
+                    self.state.totals.add_health(player, unit_type, amount);
+                }
+            }
+        }
+    }
+    // status effects of the units of the player whose turn ends wear off (infections may destroy them)
+    fn tick_effects(&mut self) {
+        // effects can also come from a position string, whatever the rules
+        let options = self.options.clone();
+        let player = self.player();
+        let coords = self.player_coords(player).collect::<Vec<_>>();
+        let mut destroyed = false;
+        for coord in coords {
+            if let Some((_, unit)) = self.get_cell_data_mut(coord).and_then(BoardCellData::player_unit_mut) {
+                if unit.effects().is_empty() {
+                    continue;
+                }
+                let (unit_type, damage) = (unit.unit_type, unit.tick_effects(&options.rules));
+                self.state.totals.remove_health(player, unit_type, damage);
+                destroyed |= self[coord].is_dead();
+                self.remove_dead(coord);
+            }
+        }
+        if destroyed {
+            self.remove_eliminated();
+        }
+    }
     pub fn is_valid_position(&self, coord : Coord) -> bool {
         let (row,col) = coord.to_tuple();
-        let is_valid = row >= 0 && col >= 0 && row < self.dim() && col < self.dim();
-        debug_assert!(is_valid,"({},{}) is not valid for a {}x{} board",row,col,self.dim(),self.dim());
-        is_valid
+        row >= 0 && col >= 0 && row < self.rows() && col < self.cols()
     }
+    // see move_legality for the reason a move is not valid
     pub fn is_valid_move(&self, from: Coord, to: Coord) -> bool {
-        self.are_in_range(from, to, 1) &&
-        self[to].is_empty() && self[from].is_unit() &&
-        self.player() == self[from].player().unwrap() &&
-        (self.options.move_while_engaged || self.can_move_while_engaged(from)
-            || (self.options.move_while_engaged_full_health && self.is_full_health(from))
-            || !self.is_engaged(from)) &&
-        (!self.options.move_only_forward || self.can_move_back(from) || self.is_moving_forward(from,to))
+        self.move_legality(from, to).is_ok()
     }
     pub fn is_full_health(&self, coord: Coord) -> bool {
         if let Some(cell) = self.get_cell(coord) {
             if let Some(unit) = cell.unit() {
-                if unit.health == unit.initial_health() {
+                if unit.health == unit.initial_health(self.rules()) {
                     return true;
                 }
             }
@@ -277,7 +452,7 @@ impl Game {
     pub fn can_move_back(&self, coord: Coord) -> bool {
         if let Some(cell) = self.get_cell(coord) {
             if let Some(unit) = cell.unit() {
-                if unit.can_move_back() {
+                if unit.can_move_back(self.rules()) {
                     return true;
                 }
             }
@@ -287,7 +462,7 @@ impl Game {
     pub fn can_move_while_engaged(&self, coord: Coord) -> bool {
         if let Some(cell) = self.get_cell(coord) {
             if let Some(unit) = cell.unit() {
-                if unit.can_move_while_engaged() {
+                if unit.can_move_while_engaged(self.rules()) {
                     return true;
                 }
             }
@@ -299,12 +474,44 @@ impl Game {
         self.is_valid_position(to) && 
         from.is_in_range(to, range)
     }
-    pub fn is_moving_forward(&self, from : Coord, to : Coord) -> bool {
-        if self.player().is_attacker() {
-            from.row-to.row > 0 || from.col-to.col > 0
-        } else {
-            to.row-from.row > 0 || to.col-from.col > 0
+    // the unit at from can attack to: along a straight line (or a diagonal if its rules allow it),
+    // within its range and with nothing blocking its line of sight in between
+    pub fn is_in_attack_range(&self, from: Coord, to: Coord) -> bool {
+        let Some(unit) = self.get_cell(from).and_then(BoardCell::unit) else {
+            return false;
+        };
+        if from == to || !self.is_valid_position(to) {
+            return false;
+        }
+        let rules = self.rules();
+        let (rows, cols) = (to.row-from.row, to.col-from.col);
+        let distance = rows.abs().max(cols.abs());
+        let straight = rows == 0 || cols == 0;
+        let diagonal = rows.abs() == cols.abs() && rules.can_attack_diagonally(unit.unit_type);
+        if !(straight || diagonal) || distance > rules.range(unit.unit_type) {
+            return false;
+        }
+        let step = Coord::new(rows.signum(), cols.signum());
+        let line_of_sight = rules.line_of_sight(unit.unit_type);
+        let mut coord = from + step;
+        while coord != to {
+            let cell = &self[coord];
+            let blocked = match line_of_sight {
+                LineOfSight::None => false,
+                LineOfSight::Walls => !cell.terrain().is_passable(),
+                // occupied cells are not passable either
+                LineOfSight::Units => !cell.is_passable(),
+            };
+            if blocked {
+                return false;
+            }
+            coord += step;
         }
+        true
+    }
+    pub fn is_moving_forward(&self, from : Coord, to : Coord) -> bool {
+        let (row, col) = self.player().forward();
+        (to.row-from.row) * row > 0 || (to.col-from.col) * col > 0
     }
     pub fn is_engaged(&self, coord: Coord) -> bool {
         let my_cell = self.get_cell(coord);
@@ -319,7 +526,7 @@ impl Game {
         coord.iter_neighbors().any(|neighbor|{
             if let Some(cell) = self.get_cell(neighbor) {
                 if let Some(player) = cell.player() {
-                    my_player != player
+                    !self.are_allies(my_player, player)
                 } else {
                     false
                 }
@@ -328,30 +535,27 @@ impl Game {
             }
         })
     }
-    pub fn unit_move(&mut self, from: Coord, to: Coord) -> Result<ActionOutcome,anyhow::Error> {
-        if self.is_valid_move(from, to) {
-            let removed = self.remove_cell(from).unwrap();
-            self.set_cell(to,removed);
-            Ok(ActionOutcome::Moved { delta: to-from })
-        } else {
-            Err(anyhow!("not a valid move"))
-        }
+    pub fn unit_move(&mut self, from: Coord, to: Coord) -> Result<ActionOutcome,WargameError> {
+        self.move_legality(from, to)?;
+        let removed = self.remove_cell(from).unwrap();
+        self.set_cell(to,removed);
+        Ok(ActionOutcome::Moved { delta: to-from })
     }
-    pub fn end_game_result(&self) -> Option<Player>{
-        assert_eq!(Player::cardinality(),2);
+    // in team games the winner stands for their whole team (see are_allies)
+    pub fn end_game_result(&self) -> Option<GameResult>{
         if self.state.deadlock {
-            // if deadlocked, we couldn't play a move so other player wins
-            return Some(self.player().next())
+            // if deadlocked, we couldn't play a move so the next opponent wins
+            return Some(GameResult::Winner(self.next_opponent(self.player())))
         } 
-        let wins_by_default = Player::Defender;
         if self.options.max_moves.is_some() && self.total_moves() >= self.options.max_moves.unwrap() {
-            return Some(wins_by_default)
+            return Some(self.move_cap_result())
         } 
-        match (self.state.attacker_has_ai,self.state.defender_has_ai) {
-            (true, true) => None,
-            (true, false) => Some(Player::Attacker),
-            (false, true) => Some(Player::Defender),
-            (false, false) => Some(wins_by_default),
+        if self.has_opponents() {
+            self.hill_result().or_else(|| self.repetition_result())
+        } else if let Some(survivor) = self.active_players().next() {
+            Some(GameResult::Winner(survivor))
+        } else {
+            Some(self.victory().tie_break)
         }
     }
     pub fn parse_move(move_str: &str) -> Option<(Coord,Coord)> {
@@ -369,7 +573,7 @@ impl Game {
         }
     }
     pub fn board_rect(&self) -> CoordPair {
-        CoordPair::from_dim(self.dim())
+        CoordPair::from_dims(self.rows(), self.cols())
     }
     pub fn rect_iter(&self) -> impl Iterator<Item = Coord> {
         self.board_rect().rect_iter()
@@ -381,23 +585,19 @@ impl Game {
         self.state.board.player_coords(player)
     }
     pub fn remove_dead(&mut self, coord: Coord) {
-        if let Some(cell) = self.get_cell(coord) {
-            if let Some((player, unit)) = cell.player_unit() {
-                if unit.health == 0 {
-                    if unit.unit_type == UnitType::AI {
-                        match *player {
-                            Player::Attacker => self.state.attacker_has_ai = false,
-                            Player::Defender => self.state.defender_has_ai = false,
-                        }
-                    }
-                    self.remove_cell(coord);
-                }
-            }
+        if self.get_cell(coord).is_some_and(|cell| cell.is_dead()) {
+            // totals (including the AI coordinates) are updated by remove_cell
+            self.remove_cell(coord);
+        }
+    }
+    pub fn perform_action(&mut self, action: Action) -> Result<ActionOutcome,WargameError> {
+        if action.into_coord_pair().is_some_and(|coords| !self.can_activate(coords.from)) {
+            return Err(IllegalActionReason::NoActionsLeft.into());
         }
-}
-    pub fn perform_action(&mut self, action: Action) -> Result<ActionOutcome,anyhow::Error> {
         match action {
             Action::Pass => Ok(ActionOutcome::Passed),
+            Action::EndTurn if self.can_end_turn() => Ok(ActionOutcome::TurnEnded),
+            Action::EndTurn => Err(IllegalActionReason::CantEndTurn.into()),
             Action::Move { from, to } => {
                 self.unit_move(from, to)
             }
@@ -410,132 +610,186 @@ impl Game {
             Action::SelfDestruct { from } => {
                 self.unit_self_destruct(from)
             }
+            Action::Spawn { at, unit_type } => {
+                self.unit_spawn(at, unit_type)
+            }
         }
     }
-    pub fn play_turn_from_action(&mut self, action: Action) -> Result<(Player,Action,ActionOutcome),anyhow::Error> {
-        let outcome = self.perform_action(action);
-        if let Ok(outcome) = outcome {
-            let player = self.player();
+    pub fn play_turn_from_action(&mut self, action: Action) -> Result<(Player,Action,ActionOutcome),WargameError> {
+        let outcome = self.perform_action(action)?;
+        let player = self.player();
+        self.state.total_actions += 1;
+        self.remove_eliminated();
+        if self.end_of_action(action) {
+            let reversible = !self.state.phase.is_irreversible();
             self.next_turn();
-            Ok((player,action,outcome))
-        } else {
-            Err(anyhow!("invalid action"))
+            self.record_position(reversible);
         }
-    }
-    pub fn play_turn_from_coords(&mut self, from: impl Into<Coord>, to: impl Into<Coord>) -> Result<(Player,Action,ActionOutcome),anyhow::Error> {
-        if let Ok(action) = self.action_from_coords(from, to) {
-            self.play_turn_from_action(action)
-        } else {
-            Err(anyhow!("invalid coordinates or move"))
-        }
-    }
-    pub fn unit_combat(&mut self, from: Coord, to: Coord) -> Result<ActionOutcome,anyhow::Error> {
-        if self.are_in_range(from, to, 1) && 
-            self[from].is_unit() && 
-            self[to].is_unit() 
-        {
-            let mutual_damage = self.options.mutual_damage;
-            let [source, target] = self.get_two_cell_data_mut(from, to).unwrap();
-            let (player_source,unit_source) = source.player_unit_mut().unwrap();
-            let (player_target,unit_target) = target.player_unit_mut().unwrap();
-            if player_source != player_target {
-                // it's an opposing unit so we try to damage it
-                let mut damage_to_source = 0;
-                if mutual_damage {
-                    damage_to_source = unit_target.apply_damage(unit_source);
+        Ok((player,action,outcome))
+    }
+    pub fn play_turn_from_coords(&mut self, from: impl Into<Coord>, to: impl Into<Coord>) -> Result<(Player,Action,ActionOutcome),WargameError> {
+        let action = self.action_from_coords(from, to)?;
+        self.play_turn_from_action(action)
+    }
+    pub fn unit_combat(&mut self, from: Coord, to: Coord) -> Result<ActionOutcome,WargameError> {
+        self.target_legality(from, to, || self.is_in_attack_range(from, to))?;
+        let options = self.options.clone();
+        // targets only strike back when the attacker is within their own range
+        let mutual_damage = options.mutual_damage && self.is_in_attack_range(to, from);
+        let rules = &options.rules;
+        let (reduction_source, reduction_target) = (self.damage_reduction(from), self.damage_reduction(to));
+        let allies = self.are_allies(self[from].player().unwrap(), self[to].player().unwrap());
+        let [source, target] = self.get_two_cell_data_mut(from, to).unwrap();
+        let (player_source,unit_source) = source.player_unit_mut().unwrap();
+        let (player_target,unit_target) = target.player_unit_mut().unwrap();
+        if !allies {
+            // it's an opposing unit so we try to damage it
+            let (health_source, health_target) = (unit_source.health, unit_target.health);
+            let (shield_source, shield_target) = (unit_source.has_shield(rules), unit_target.has_shield(rules));
+            let mut effects = CombatEffects::default();
+            let mut damage_to_source = 0;
+            if mutual_damage {
+                damage_to_source = unit_target.apply_damage(unit_source, rules, reduction_source);
+                if damage_to_source > 0 {
+                    effects.source = unit_target.inflict_effects(unit_source, rules);
                 }
-                let damage_to_target = unit_source.apply_damage(unit_target);
-                self.remove_dead(from);
-                self.remove_dead(to);
-                Ok(ActionOutcome::Damaged { to_source: damage_to_source, to_target: damage_to_target })
-            } else {
-                Err(anyhow!("can't attack friendly units"))
             }
+            let damage_to_target = unit_source.apply_damage(unit_target, rules, reduction_target);
+            if damage_to_target > 0 {
+                effects.target = unit_source.inflict_effects(unit_target, rules);
+            }
+            effects.source.absorbed = shield_source && !unit_source.has_shield(rules);
+            effects.target.absorbed = shield_target && !unit_target.has_shield(rules);
+            let lost_source = (*player_source, unit_source.unit_type, health_source - unit_source.health);
+            let lost_target = (*player_target, unit_target.unit_type, health_target - unit_target.health);
+            for (player, unit_type, amount) in [lost_source, lost_target] {
+                self.state.totals.remove_health(player, unit_type, amount);
+            }
+            self.remove_dead(from);
+            self.remove_dead(to);
+            Ok(ActionOutcome::Damaged { to_source: damage_to_source, to_target: damage_to_target, effects })
+        } else if player_source == player_target {
+            Err(IllegalActionReason::FriendlyUnit.into())
         } else {
-            Err(anyhow!("out of range or invalid coordinates"))
+            Err(IllegalActionReason::AlliedUnit.into())
         }
     }
-    pub fn unit_repair(&mut self, from: Coord, to: Coord) -> Result<ActionOutcome,anyhow::Error> {
-        if self.are_in_range(from, to, 1) && 
-            self[from].is_unit() && 
-            self[to].is_unit() 
-        {
-            let [source, target] = self.get_two_cell_data_mut(from, to).unwrap();
-            let (player_source,unit_source) = source.player_unit_mut().unwrap();
-            let (player_target,unit_target) = target.player_unit_mut().unwrap();
-            if player_source == player_target {
-                // it's a friendly unit so we can try to repair it
-                let repair_amount = unit_source.apply_repair(unit_target);
-                Ok(ActionOutcome::Repaired { amount: repair_amount })
-            } else {
-                Err(anyhow!("can only repair friendly units"))
-            }
+    pub fn unit_repair(&mut self, from: Coord, to: Coord) -> Result<ActionOutcome,WargameError> {
+        self.target_legality(from, to, || self.are_in_range(from, to, 1))?;
+        let options = self.options.clone();
+        let [source, target] = self.get_two_cell_data_mut(from, to).unwrap();
+        let (player_source,unit_source) = source.player_unit_mut().unwrap();
+        let (player_target,unit_target) = target.player_unit_mut().unwrap();
+        if player_source == player_target {
+            // it's a friendly unit so we can try to repair it
+            let health_target = unit_target.health;
+            let repair_amount = unit_source.apply_repair(unit_target, &options.rules);
+            let (player, unit_type, amount) = (*player_target, unit_target.unit_type, unit_target.health - health_target);
+            self.state.totals.add_health(player, unit_type, amount);
+            Ok(ActionOutcome::Repaired { amount: repair_amount })
         } else {
-            Err(anyhow!("out of range or invalid coordinates"))
+            Err(IllegalActionReason::OpposingUnit.into())
         }
     }
-    pub fn unit_self_destruct(&mut self, from: Coord) -> Result<ActionOutcome,anyhow::Error> {
+    pub fn unit_self_destruct(&mut self, from: Coord) -> Result<ActionOutcome,WargameError> {
         if self.is_valid_position(from) && self[from].is_unit() {
+            let options = self.options.clone();
             let mut total_damage = 0;
             for to in from.rect_around(1).rect_iter() {
                 if from == to || !self.is_valid_position(to) || self[to].is_empty() {
                     continue;
                 }
+                let reduction = self.damage_reduction(to);
                 let [source, target] = self.get_two_cell_data_mut(from, to).unwrap();
                 let (_,unit_source) = source.player_unit_mut().unwrap();
-                let (_,unit_target) = target.player_unit_mut().unwrap();
-                total_damage += unit_source.apply_self_destruct(unit_target);
+                let (player_target,unit_target) = target.player_unit_mut().unwrap();
+                let health_target = unit_target.health;
+                total_damage += unit_source.apply_self_destruct(unit_target, &options.rules, reduction);
+                let (player, unit_type, amount) = (*player_target, unit_target.unit_type, health_target - unit_target.health);
+                self.state.totals.remove_health(player, unit_type, amount);
                 self.remove_dead(to);
             }
-            let (_, source) = self.get_cell_data_mut(from).expect("not empty").player_unit_mut().expect("not empty");
+            let (player, source) = self.get_cell_data_mut(from).expect("not empty").player_unit_mut().expect("not empty");
+            let (player, unit_type, amount) = (*player, source.unit_type, source.health);
             source.kill();
+            self.state.totals.remove_health(player, unit_type, amount);
             self.remove_dead(from);
             Ok(ActionOutcome::SelfDestructed{total_damage})
+        } else if !self.is_valid_position(from) {
+            Err(IllegalActionReason::OffBoard.into())
         } else {
-            Err(anyhow!("invalid coordinates"))
+            Err(IllegalActionReason::NoUnit.into())
         }
     }
-    pub fn action_from_coords(&self, from: impl Into<Coord>, to: impl Into<Coord>) -> Result<Action,anyhow::Error> {
+    // infers the action from the contents of the cells (see action_legality for explicit actions)
+    pub fn action_from_coords(&self, from: impl Into<Coord>, to: impl Into<Coord>) -> Result<Action,IllegalActionReason> {
         let (from, to) = (from.into(),to.into());
-        if self.are_in_range(from, to, 1) && 
-            self[from].is_unit() && 
-            self.player() == self[from].player().unwrap()
-        {
-            // it's our turn and we are acting on our own unit
-            if from == to {
-                // destination is same as source => self destruction!
-                Ok(Action::SelfDestruct { from })
-            } else if self.is_valid_move(from, to) {
-                // destination empty and move validated (not engaged, etc...)
-                Ok(Action::Move { from, to })
-            } else if self[to].is_unit() {
-                // destination is a unit
-                let (player_source,unit_source) = self[from].player_unit().unwrap();
-                let (player_target,unit_target) = self[to].player_unit().unwrap();
-                if player_source != player_target {
-                    // it's an opposing unit so we try to damage it (it will damage us back)
-                    if unit_source.can_damage(unit_target) {
-                        Ok(Action::Attack { from, to })
-                    } else {
-                        Err(anyhow!("can't damage unit"))
-                    }
+        // it's our turn and we are acting on our own unit
+        self.source_legality(from)?;
+        if !self.is_valid_position(to) {
+            return Err(IllegalActionReason::OffBoard);
+        }
+        if from == to {
+            // destination is same as source => self destruction!
+            return Ok(Action::SelfDestruct { from });
+        }
+        let adjacent = self.are_in_range(from, to, 1);
+        let (player_source,unit_source) = self[from].player_unit().unwrap();
+        match self[to].player_unit() {
+            // destination empty (or a wall) so we try to move there
+            None if adjacent => self.move_legality(from, to).map(|_| Action::Move { from, to }),
+            None if self.is_in_attack_range(from, to) => Err(IllegalActionReason::NoTarget),
+            None => Err(IllegalActionReason::OutOfRange),
+            Some((player_target,unit_target)) if player_target == player_source => {
+                // it's our unit so we try to repair it (if repair not possible then action is not valid)
+                if !adjacent {
+                    Err(IllegalActionReason::OutOfRange)
+                } else if self.rules().repair_amount(unit_source.unit_type, unit_target.unit_type) == 0 {
+                    Err(IllegalActionReason::CantRepair)
+                } else if !unit_source.can_repair(unit_target, self.rules()) {
+                    Err(IllegalActionReason::FullHealth)
                 } else {
-                    // it's our unit so we try to repair it (if repair not possible then action is not valid)
-                    if unit_source.can_repair(unit_target) {
-                        Ok(Action::Repair { from, to })
-                    } else {
-                        Err(anyhow!("can't repair unit"))
-                    }
+                    Ok(Action::Repair { from, to })
                 }
-            } else {
-                Err(anyhow!("invalid target coordinate"))
-            }
-        } else {
-            Err(anyhow!("not in range or source is not friendly unit"))
+            },
+            Some((player_target,_)) if self.are_allies(*player_source, *player_target) => Err(IllegalActionReason::AlliedUnit),
+            Some((_,unit_target)) => {
+                // it's an opposing unit so we try to damage it (it will damage us back if it's in range),
+                // attacks at a distance (ranged units or diagonal attacks) need a target in sight
+                if !self.is_in_attack_range(from, to) {
+                    Err(IllegalActionReason::OutOfRange)
+                } else if !adjacent && !self.is_visible(*player_source, to) {
+                    Err(IllegalActionReason::NotVisible)
+                } else if !unit_source.can_damage(unit_target, self.rules()) {
+                    Err(IllegalActionReason::CantDamage)
+                } else {
+                    Ok(Action::Attack { from, to })
+                }
+            },
         }
     }
+    // actions of the units of the player to move (including spawns next to their AI)
+    pub fn possible_unit_actions(&self) -> impl Iterator<Item=Action> + '_ {
+        self.player_unit_coords(self.player())
+            .flat_map(|(coord,_)| self.possible_actions_from_coord(coord))
+            .chain(self.possible_spawns())
+    }
+    pub fn possible_actions(&self) -> impl Iterator<Item=Action> + '_ {
+        self.possible_unit_actions().chain(self.can_end_turn().then_some(Action::EndTurn))
+    }
+    // see action_legality for the reason an action is not legal
+    pub fn is_legal_action(&self, action: Action) -> bool {
+        self.action_legality(action).is_ok()
+    }
     pub fn possible_actions_from_coord(&self, source : Coord) -> impl Iterator<Item=Action> + '_ {
-        let rect_iter = source.rect_around(1).rect_iter();
+        let range = self.get_cell(source).and_then(BoardCell::unit).map(|unit| self.rules().range(unit.unit_type)).unwrap_or(1);
+        // the cells within range, on the board (without overflows at its edges)
+        let range = range.max(1);
+        let (rows, cols) = (self.rows(), self.cols());
+        let rect = CoordPair::new(
+            Coord::new(source.row.saturating_sub(range).clamp(0, rows-1), source.col.saturating_sub(range).clamp(0, cols-1)),
+            Coord::new(source.row.saturating_add(range).clamp(0, rows-1), source.col.saturating_add(range).clamp(0, cols-1)));
+        let rect_iter = rect.rect_iter();
         rect_iter.filter_map(move|target|self.action_from_coords(source, target).ok())
     }
     pub fn player_unit_coords(&self, player: Player) -> impl Iterator<Item = (Coord,&BoardCell)> + '_ {
@@ -559,7 +813,7 @@ impl Game {
                     Some((CoordPair::new(from,to),from_unit,to_unit))
                 }))
     }
-    pub fn heuristic(&self, player: Player, maximizing_player: bool, depth: usize, opt_end_game_result: Option<Option<Player>>) -> HeuristicScore {
+    pub fn heuristic(&self, player: Player, maximizing_player: bool, depth: usize, opt_end_game_result: Option<Option<GameResult>>) -> HeuristicScore {
         let result = if let Some(end_game_result) = opt_end_game_result {
             end_game_result
         } else {
@@ -567,8 +821,10 @@ impl Game {
         };
         let moves = self.total_moves() as HeuristicScore;
         let score = match result {
-            Some(winner) => {
-                if winner == player {
+            // neither side gains anything from a draw
+            Some(GameResult::Draw) => 0,
+            Some(GameResult::Winner(winner)) => {
+                if self.are_allies(winner, player) {
                     // quicker win is better
                     HeuristicScore::MAX - moves  
                 } else {
@@ -578,12 +834,7 @@ impl Game {
             }
             // not finished so call appropriate heuristic
             None => {
-                let heuristic = match (player.is_attacker(), maximizing_player) {
-                    (true, true) => self.options.heuristics.attacker_max.clone(),
-                    (true, false) => self.options.heuristics.attacker_min.clone(),
-                    (false, true) => self.options.heuristics.defender_max.clone(),
-                    (false, false) => self.options.heuristics.defender_min.clone(),
-                };
+                let heuristic = self.options.heuristics.get(player, maximizing_player);
                 heuristic(self,player)
             }
         };
@@ -612,7 +863,7 @@ impl Game {
                 timeout = true;
             }
         }
-        let mut opt_end_game_result : Option<Option<Player>> = None;
+        let mut opt_end_game_result : Option<Option<GameResult>> = None;
         if timeout && self.options.min_depth.is_some() && depth >= self.options.min_depth.unwrap()
             || self.options.max_depth.is_some() && depth >= self.options.max_depth.unwrap()
             || { 
@@ -627,10 +878,8 @@ impl Game {
             let mut best_score;
             let mut total_depth = 0.0;
             let mut total_count = 0;
-            let mut possible_actions = self.player_unit_coords(self.player())
-                .map(|(coord,_)| coord)
-                .flat_map(|coord|self.possible_actions_from_coord(coord))
-                .collect::<Vec<_>>();
+            // with action points the player stays the same for the sub-moves of a turn
+            let mut possible_actions = self.possible_actions().collect::<Vec<_>>();
             if self.options.rand_traversal {
                 possible_actions.shuffle(&mut rand::thread_rng());
             }
@@ -644,7 +893,9 @@ impl Game {
             for possible_action in possible_actions {
                 let mut possible_game = self.clone();
                 possible_game.play_turn_from_action(possible_action).expect("action should be valid");
-                let (score, _, rec_avg_depth) = possible_game.minimax_alpha_beta(!maximizing_player, player, depth+1, alpha, beta, start_time);
+                // paranoid search: the searching player's side maximizes and everyone else minimizes
+                let maximizing = possible_game.are_allies(player, possible_game.player());
+                let (score, _, rec_avg_depth) = possible_game.minimax_alpha_beta(maximizing, player, depth+1, alpha, beta, start_time);
                 total_depth += rec_avg_depth;
                 total_count += 1;
                 if maximizing_player && score >= best_score || !maximizing_player && score <= best_score {
@@ -688,7 +939,7 @@ impl Game {
                 timeout = true;
             }
         }
-        let mut opt_end_game_result : Option<Option<Player>> = None;
+        let mut opt_end_game_result : Option<Option<GameResult>> = None;
         if timeout && self.options.min_depth.is_some() && depth >= self.options.min_depth.unwrap()
             || self.options.max_depth.is_some() && depth >= self.options.max_depth.unwrap()
             || { 
@@ -711,9 +962,7 @@ impl Game {
             let mut state = State::default();
             state.alpha = alpha_parent;
             state.beta = beta_parent;
-            let mut possible_actions = self.player_unit_coords(self.player())
-                .flat_map(|coord|self.possible_actions_from_coord(coord))
-                .collect::<Vec<_>>();
+            let mut possible_actions = self.possible_actions().collect::<Vec<_>>();
             if self.options.rand_traversal {
                 possible_actions.shuffle(&mut rand::thread_rng());
             }
@@ -734,10 +983,11 @@ impl Game {
                 if !prune {
                     let mut possible_game = self.clone();
                     possible_game.play_turn_from_action(possible_action).expect("action should be valid");
+                    let maximizing = possible_game.are_allies(player, possible_game.player());
                     let (score, _, rec_avg_depth) = if self.options.parallel_levels-1 > depth {
-                        possible_game.minimax_alpha_beta_par(!maximizing_player, player, depth+1, state.alpha, state.beta, start_time)
+                        possible_game.minimax_alpha_beta_par(maximizing, player, depth+1, state.alpha, state.beta, start_time)
                     } else {
-                        possible_game.minimax_alpha_beta(!maximizing_player, player, depth+1, state.alpha, state.beta, start_time)
+                        possible_game.minimax_alpha_beta(maximizing, player, depth+1, state.alpha, state.beta, start_time)
                     };
                     state.total_depth += rec_avg_depth;
                     state.total_count += 1;
@@ -779,6 +1029,9 @@ impl Game {
         }
     }
     pub fn suggest_action(&mut self) -> (HeuristicScore, Option<Action>, f32, f32) {
+        if self.options.fog_radius.is_some() {
+            return self.suggest_action_determinized();
+        }
         let start_time = Instant::now();
         #[cfg(not(feature="rayon"))]
         let (score, suggestion, avg_depth) = 
@@ -851,6 +1104,20 @@ impl Game {
         } else {
             writeln!(w,"{} moves played",self.total_moves())?;
         }
+        if self.action_points().is_some() {
+            match self.actions_left() {
+                Some(actions_left) => writeln!(w,"{} actions left this turn",actions_left)?,
+                None => writeln!(w,"{} actions played this turn",self.turn_phase().actions())?,
+            }
+        }
+        if self.reinforcements().is_some() {
+            let reserve = self.reserve(self.player());
+            write!(w,"{} spawns left",reserve.spawns)?;
+            if reserve.spawns > 0 && reserve.cooldown > 0 {
+                write!(w," (next one in {} turns)",reserve.cooldown)?;
+            }
+            writeln!(w)?;
+        }
         if self.options.debug {
             if let Some(max_depth) = self.options.max_depth {
                 writeln!(w,"Current max search depth: {}",max_depth)?;
@@ -903,38 +1170,58 @@ impl Game {
         Ok(())
     }
     pub fn pretty_print_board(&self, w: &mut impl IoWrite) -> IoResult<()> {
+        self.pretty_print_board_for(w, None)
+    }
+    // board as seen by the viewer under fog of war (hidden cells only show their terrain)
+    pub fn pretty_print_board_for(&self, w: &mut impl IoWrite, viewer: Option<Player>) -> IoResult<()> {
+        let observation = viewer.map(|player| self.observation(player));
         write!(w,"    ")?;
-        for col in 0..self.dim() {
+        for col in 0..self.cols() {
             write!(w," {:>2} ",col)?;
         }
         writeln!(w)?;
-        for row in 0..self.dim() {
+        for row in 0..self.rows() {
             write!(w,"{:>2}: ",(row as u8 + b'A') as char)?;
-            for col in 0..self.dim() {
-                let cell = self[Coord::new(row,col)];
-                write!(w," {}",cell.to_pretty_compact_string())?;
+            for col in 0..self.cols() {
+                let coord = Coord::new(row,col);
+                let cell = self[coord];
+                let marker = cell.terrain().marker().unwrap_or(' ');
+                if observation.as_ref().is_some_and(|o| !o.is_visible(coord)) && cell.terrain().is_passable() {
+                    write!(w,"{} ~ ",marker)?;
+                } else {
+                    write!(w,"{}{}",marker,cell.to_pretty_compact_string())?;
+                }
             }
             writeln!(w)?;
         }
         Ok(())
     }
     pub fn pretty_print(&self, w: &mut impl IoWrite) -> IoResult<()> {
+        self.pretty_print_for(w, None)
+    }
+    pub fn pretty_print_for(&self, w: &mut impl IoWrite, viewer: Option<Player>) -> IoResult<()> {
         self.pretty_print_info(w)?;
         writeln!(w)?;
-        self.pretty_print_board(w)
-    }
-    pub fn human_play_turn_from_coords(&mut self, opt_w: Option<&mut impl IoWrite>, from: impl Into<Coord>, to: impl Into<Coord>) -> IoResult<bool> {
-        if let Ok((player, action, outcome)) = self.play_turn_from_coords(from, to) {
-            if let Some(w) = opt_w {
-                writeln!(w,"{}: {}", player, action)?;
-                if outcome.is_useful_info() {
-                    writeln!(w,"{}", outcome)?;
-                }
+        self.pretty_print_board_for(w, viewer)
+    }
+    pub fn human_play_turn_from_coords(&mut self, opt_w: Option<&mut impl IoWrite>, from: impl Into<Coord>, to: impl Into<Coord>) -> IoResult<Result<(),IllegalActionReason>> {
+        match self.action_from_coords(from, to) {
+            Ok(action) => self.human_play_turn_from_action(opt_w, action),
+            Err(reason) => Ok(Err(reason)),
         }
-            Ok(true)
-        } else {
-            Ok(false)
+    }
+    pub fn human_play_turn_from_action(&mut self, opt_w: Option<&mut impl IoWrite>, action: Action) -> IoResult<Result<(),IllegalActionReason>> {
+        if let Err(reason) = self.action_legality(action) {
+            return Ok(Err(reason));
+        }
+        let (player, action, outcome) = self.play_turn_from_action(action).expect("legal actions can be played");
+        if let Some(w) = opt_w {
+            writeln!(w,"{}: {}", player, action)?;
+            if outcome.is_useful_info() {
+                writeln!(w,"{}", outcome)?;
+            }
         }
+        Ok(Ok(()))
     }
     pub fn computer_play_turn(&mut self, opt_w: Option<&mut impl IoWrite>) -> IoResult<Option<Action>> {
         let (score,best_action,elapsed_seconds,avg_depth) = self.suggest_action();
@@ -972,6 +1259,10 @@ impl Game {
 impl std::fmt::Display for Game {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f,"{}",self.player().to_first_letter())?;
+        // square boards are implied by the number of cells
+        if self.rows() != self.cols() {
+            write!(f,":{}x{}",self.rows(),self.cols())?;
+        }
         for c in self.rect_iter() {
             write!(f,":{}",self.get_cell(c).unwrap())?;
         }
@@ -979,6 +1270,60 @@ impl std::fmt::Display for Game {
     }
 }
 
+impl Game {
+    // parses the position string produced by Display (the board dimensions follow the player
+    // for rectangular boards, otherwise a square board is deduced from the number of cells)
+    pub fn from_position_string(position: &str, options: GameOptions) -> Result<Self,anyhow::Error> {
+        let mut fields = position.trim().split(':').peekable();
+        let player_str = fields.next().unwrap_or_default();
+        let player = Player::all()
+            .find(|p| p.to_first_letter().to_string() == player_str)
+            .ok_or(anyhow!("invalid player: {player_str}"))?;
+        let dims = fields.next_if(|f| f.contains('x')).map(parse_dims).transpose()?;
+        let cells = fields.map(str::parse::<BoardCell>).collect::<Result<Vec<_>,_>>()?;
+        let (rows, cols) = match dims {
+            Some(dims) => dims,
+            None => {
+                let dim = (cells.len() as f64).sqrt() as usize;
+                if dim == 0 || dim * dim != cells.len() || dim > Dim::MAX as usize {
+                    return Err(anyhow!("{} cells do not make a square board", cells.len()));
+                }
+                (dim as Dim, dim as Dim)
+            }
+        };
+        if rows as usize * cols as usize != cells.len() {
+            return Err(anyhow!("{} cells do not make a {rows}x{cols} board", cells.len()));
+        }
+        let mut options = options;
+        (options.rows, options.cols) = (rows, cols);
+        // players with units on the board (or to move) join the players of the options
+        options.players = Player::all()
+            .filter(|p| options.players.contains(p) || *p == player || cells.iter().any(|cell| cell.player() == Some(*p)))
+            .collect();
+        let mut game = Self::new_empty(options);
+        game.state.player = player;
+        for (coord, cell) in game.board_rect().rect_iter().zip(cells) {
+            if let Some(unit) = cell.unit() {
+                if unit.health > unit.initial_health(game.rules()) {
+                    return Err(anyhow!("health too high in cell: {cell}"));
+                }
+            }
+            game.set_terrain(coord, cell.terrain());
+            game.set_cell(coord, cell);
+        }
+        game.state.initial_totals = game.state.totals;
+        game.record_position(false);
+        Ok(game)
+    }
+}
+
+impl std::str::FromStr for Game {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_position_string(s, Default::default())
+    }
+}
+
 impl std::ops::Index<Coord> for Game {
     type Output = BoardCell;
     fn index(&self, coord: Coord) -> & Self::Output {
@@ -1,87 +1,167 @@
-use anyhow::anyhow;
 use reqwest::header::CONTENT_TYPE;
-use serde::{Serialize, Deserialize};
+use reqwest::blocking::{Client, Response};
 
-use crate::{Coord, Game, CoordPair};
+use crate::{Game, Player, Action, ActionOutcome, WargameError, BrokerError, protocol::{BrokerData, BrokerReply}};
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
-struct BrokerData {
-    from: Coord,
-    to: Coord,
-    turn: usize,
+use std::time::Duration;
+
+impl From<reqwest::Error> for WargameError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            BrokerError::Timeout(error.to_string()).into()
+        } else {
+            BrokerError::Request(error.to_string()).into()
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct BrokerReply {
-    success: bool,
-    error: Option<String>,
-    data: Option<BrokerData>,    
+impl From<serde_json::Error> for WargameError {
+    fn from(error: serde_json::Error) -> Self {
+        BrokerError::Data(error.to_string()).into()
+    }
+}
+
+// data of a reply (errors come with a 404 status)
+fn broker_reply(res: Response) -> Result<Option<BrokerData>,WargameError> {
+    let status = res.status().as_u16();
+    match status {
+        200 | 404 => {
+            let broker_reply : BrokerReply = serde_json::from_str(res.text()?.as_str())?;
+            match broker_reply {
+                BrokerReply { success: true, data, .. } if status == 200 => Ok(data),
+                BrokerReply { error: Some(error), .. } => Err(BrokerError::Reply(error).into()),
+                _ => Err(BrokerError::Unknown.into()),
+            }
+        },
+        status => {
+            Err(BrokerError::Status(status).into())
+        }
+    }
 }
 
 impl Game {
-    pub fn broker_post_move(&self, action_move: CoordPair) -> Result<(),anyhow::Error> {
-        let turn = self.total_moves();
-        let from = action_move.from;
-        let to = action_move.to;
-        let data = BrokerData{ from, to, turn };
-        let client = reqwest::blocking::Client::new();
-        let broker_url = self.options().broker.as_ref().ok_or(anyhow!("no broker"))?.clone();
-        let res = client.post(broker_url)
-            .body(serde_json::to_string(&data)?)
-            .header(CONTENT_TYPE, "application/json")
-            .send()?;
-        let status = res.status().as_u16();
-        match status {
-            200 | 404 => {
-                let broker_reply : BrokerReply = serde_json::from_str(res.text()?.as_str())?;
-                if status == 404 && broker_reply.error.is_some() {
-                    return Err(anyhow!("Broker error: {}", broker_reply.error.unwrap()));
-                }
-                if status == 200 && broker_reply.success && broker_reply.data.is_some() {
-                    let check_data = broker_reply.data.unwrap();
-                    if data == check_data {
-                        return Ok(());
-                    }
-                }
-                Err(anyhow!("Broker error: unknown"))
-            },
-            status => {
-                Err(anyhow!("Broker error: http status {status}"))
+    fn broker_url(&self) -> Result<String,WargameError> {
+        Ok(self.options().broker.clone().ok_or(BrokerError::NoBroker)?)
+    }
+    fn broker_client(&self) -> Result<Client,WargameError> {
+        let timeout = Duration::from_secs_f32(self.options().broker_timeout.max(0.001));
+        Ok(Client::builder().timeout(timeout).connect_timeout(timeout).build()?)
+    }
+    // delay after a number of failed requests or empty polls in a row
+    pub fn broker_backoff(&self, attempts: usize) -> Duration {
+        let options = self.options();
+        let delay = options.broker_min_delay * 2f32.powi(attempts.min(30) as i32);
+        Duration::from_secs_f32(delay.min(options.broker_max_delay).max(0.0))
+    }
+    // the request again after transient failures (see BrokerError::is_transient), up to broker_max_failures
+    fn broker_retry<T>(&self, request: impl Fn(&Client) -> Result<T,WargameError>) -> Result<T,WargameError> {
+        let client = self.broker_client()?;
+        let mut failures = 0;
+        loop {
+            match request(&client) {
+                Err(WargameError::Broker(error)) if error.is_transient() && failures+1 < self.options().broker_max_failures => {
+                    std::thread::sleep(self.broker_backoff(failures));
+                    failures += 1;
+                },
+                result => return result,
             }
         }
     }
-    pub fn broker_get_move(&self) -> Result<Option<CoordPair>,anyhow::Error> {
-        let broker_url = self.options().broker.as_ref().ok_or(anyhow!("no broker"))?.clone();
-        let res = reqwest::blocking::get(broker_url)?;
-        let status = res.status().as_u16();
-        match status {
-            200 | 404 => {
-                let broker_reply : BrokerReply = serde_json::from_str(res.text()?.as_str())?;
-                if status == 404 && broker_reply.error.is_some() {
-                    return Err(anyhow!("Broker error: {}", broker_reply.error.unwrap()));
-                }
-                if status == 200 && broker_reply.success {
-                    if let Some(data) = broker_reply.data {
-                        // broker has some data for us
-                        if data.turn == self.total_moves()+1 {
-                            // the broker has our next move
-                            return Ok(Some(CoordPair::new(data.from, data.to)))
-                        } else {
-                            // return Err(anyhow!("Broker error: expecting data for turn {}, got turn {}",self.total_moves()+1,data.turn))
-                            return Ok(None)
-                        }
-                    } else {
-                        // broker has no data yet
-                        // return Err(anyhow!("Broker error: no data available yet"));
-                        return Ok(None)
-                    }
-                } else {
-                    Err(anyhow!("Broker error: unknown"))
-                }
-            },
-            status => {
-                Err(anyhow!("Broker error: http status {status}"))
+    // move of a turn (the latest move without a turn or with a broker that ignores it)
+    fn broker_get_data(&self, turn: Option<usize>) -> Result<Option<BrokerData>,WargameError> {
+        let url = self.broker_url()?;
+        let url = match turn {
+            Some(turn) => format!("{url}{}turn={turn}", if url.contains('?') { '&' } else { '?' }),
+            None => url,
+        };
+        self.broker_retry(|client| broker_reply(client.get(&url).send()?))
+    }
+    fn broker_post(&self, data: BrokerData) -> Result<BrokerData,WargameError> {
+        let broker_url = self.broker_url()?;
+        let body = serde_json::to_string(&data)?;
+        // the broker accepts the same data again if a reply was lost
+        let check_data = self.broker_retry(|client| broker_reply(client.post(&broker_url)
+            .body(body.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .send()?))?;
+        Ok(check_data.ok_or(BrokerError::Unknown)?)
+    }
+    // posts the action just played, passes included (broker turns count actions, see Game::total_actions)
+    pub fn broker_post_move(&self, action: Action) -> Result<(),WargameError> {
+        let data = BrokerData::new(action, self.total_actions());
+        if self.broker_post(data)? == data {
+            Ok(())
+        } else {
+            Err(BrokerError::Unknown.into())
+        }
+    }
+    // reports the result once the game is over (every client of the game may report it)
+    pub fn broker_post_result(&self) -> Result<(),WargameError> {
+        let Some(result) = self.end_game_result() else {
+            return Ok(());
+        };
+        let data = BrokerData::new_result(result, self.total_actions());
+        let check_data = self.broker_post(data)?;
+        if (check_data.turn, check_data.result) == (data.turn, data.result) {
+            Ok(())
+        } else {
+            Err(BrokerError::Unknown.into())
+        }
+    }
+    // the action of broker data (version 1 moves are interpreted from the cells)
+    fn broker_action(&self, data: &BrokerData) -> Result<Action,WargameError> {
+        let action = match (data.action(), data.kind, data.from, data.to) {
+            (Some(action), _, _, _) => action,
+            (None, None, Some(from), Some(to)) => self.action_from_coords(from, to)?,
+            _ => return Err(BrokerError::Data(format!("incomplete move data for turn {}", data.turn)).into()),
+        };
+        self.action_legality(action)?;
+        Ok(action)
+    }
+    // a game that ended before this one started is still on the channel (the first action of this game replaces it)
+    fn broker_finished_channel(&self) -> Result<bool,WargameError> {
+        Ok(self.total_actions() == 0 && self.broker_get_data(None)?.is_some_and(|data| data.result.is_some()))
+    }
+    // the action of the next turn if the broker has it
+    pub fn broker_get_move(&self) -> Result<Option<Action>,WargameError> {
+        if self.broker_finished_channel()? {
+            return Ok(None);
+        }
+        let expected = self.total_actions()+1;
+        match self.broker_get_data(Some(expected))? {
+            Some(data) if data.turn == expected => Ok(Some(self.broker_action(&data)?)),
+            // brokers without turn queries reply with their latest move
+            Some(data) if data.turn < expected => Ok(None),
+            Some(data) => Err(BrokerError::Desync { expected, got: data.turn }.into()),
+            None => Ok(None),
+        }
+    }
+    // polls the broker for the move of the next turn, waiting longer between empty polls
+    pub fn broker_wait_move(&self) -> Result<Action,WargameError> {
+        let mut polls = 0;
+        loop {
+            if let Some(action) = self.broker_get_move()? {
+                return Ok(action);
             }
+            std::thread::sleep(self.broker_backoff(polls));
+            polls += 1;
+        }
+    }
+    // plays the moves the broker has past the turn of the game (after a restart or a lost reply)
+    pub fn broker_resync(&mut self) -> Result<Vec<(Player,Action,ActionOutcome)>,WargameError> {
+        if self.broker_finished_channel()? {
+            return Ok(Vec::new());
+        }
+        let latest = self.broker_get_data(None)?.map_or(0, |data| data.turn);
+        let mut played = Vec::new();
+        while self.total_actions() < latest && self.end_game_result().is_none() {
+            let expected = self.total_actions()+1;
+            let data = self.broker_get_data(Some(expected))?
+                .filter(|data| data.turn == expected)
+                .ok_or(BrokerError::Desync { expected, got: latest })?;
+            let action = self.broker_action(&data)?;
+            played.push(self.play_turn_from_action(action)?);
         }
+        Ok(played)
     }
 }
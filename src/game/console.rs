@@ -1,47 +1,62 @@
-use crate::{Game, Coord, UnitType};
+use crate::{Game, Coord, UnitType, Player, Action, ParseActionError, WargameError, game::IllegalActionReason};
+#[cfg(not(feature="broker"))]
+use crate::BrokerError;
 
 use std::io::Write as IoWrite;
 use std::io::{stdout,stdin};
 
+// what a player entered in the console (see Game::console_read_move)
+#[derive(Debug)]
+pub enum ConsoleCommand {
+    Play(Action),
+    // looks like an action but can't be played (the reason is shown to the player)
+    Invalid(WargameError),
+    // no action recognized: shows the help and the damage tables
+    Help,
+    Quit,
+}
+
 impl Game {
     pub fn console_pretty_print(&self) {
-        self.pretty_print(&mut stdout()).expect("no errors on stdout");
+        self.console_pretty_print_for(None);
+    }
+    pub fn console_pretty_print_for(&self, viewer: Option<Player>) {
+        self.pretty_print_for(&mut stdout(), viewer).expect("no errors on stdout");
         stdout().flush().expect("no errors on stdout");
     }
-    pub fn console_read_move(&self) -> Result<(Coord,Coord),String> {
+    // an action in the explicit notation or a coordinate pair, or a command (errors come from the broker)
+    pub fn console_read_move(&self) -> Result<ConsoleCommand,WargameError> {
         if self.options().broker.is_none() {
             print!("{} player, enter your next move: ",self.player());
-            stdout().flush().expect("no errors on stdout");
-            let input = stdin().lines().next().unwrap().expect("no errors on stdin");
-            let parsed = Self::parse_move(&input);
-            parsed.ok_or(input)
+            stdout().flush()?;
+            let input = stdin().lines().next().unwrap_or(Ok(String::from("quit")))?;
+            let input = input.trim();
+            Ok(match self.parse_action(input) {
+                Ok(action) => ConsoleCommand::Play(action),
+                _ if input == "quit" || input == "exit" => ConsoleCommand::Quit,
+                Err(WargameError::Parse(ParseActionError::UnknownKind(_) | ParseActionError::Empty)) => ConsoleCommand::Help,
+                // coordinate pairs that make no legal action and malformed explicit actions
+                Err(error) => ConsoleCommand::Invalid(error),
+            })
         } else {
             #[cfg(feature="broker")]
-            match self.broker_get_move() {
-                Ok(Some(coords)) => {
-                    Ok((coords.from,coords.to))
-                },
-                Ok(None) => {
-                    Err("broker retry".to_string())
-                },
-                Err(error) => {
-                    eprintln!("{}",error);
-                    std::process::exit(1);
-                },
-            }
+            { Ok(ConsoleCommand::Play(self.broker_wait_move()?)) }
             #[cfg(not(feature="broker"))]
-            {
-                eprintln!("broker feature not enabled");
-                std::process::exit(1);
-            }
+            Err(BrokerError::NoBroker.into())
         }
     }
-    pub fn console_human_play_turn_from_coords(&mut self, from: impl Into<Coord>, to: impl Into<Coord>) -> bool {
+    pub fn console_human_play_turn_from_coords(&mut self, from: impl Into<Coord>, to: impl Into<Coord>) -> Result<(),IllegalActionReason> {
         let result = self.human_play_turn_from_coords(Some(&mut stdout()), from, to).expect("no errors on stdout");
         stdout().flush().expect("no errors on stdout");
         result
     }
-    pub fn console_human_play_turn(&mut self) {
+    pub fn console_human_play_turn_from_action(&mut self, action: Action) -> Result<(),IllegalActionReason> {
+        let result = self.human_play_turn_from_action(Some(&mut stdout()), action).expect("no errors on stdout");
+        stdout().flush().expect("no errors on stdout");
+        result
+    }
+    // returns the action played (None if deadlocked), errors come from the broker
+    pub fn console_human_play_turn(&mut self) -> Result<Option<Action>,WargameError> {
         let mut options = self.clone_options();
         options.max_depth = Some(4);
         options.max_seconds = Some(0.5);
@@ -50,49 +65,60 @@ impl Game {
         if let (_, Some(suggestion),_,_) = game_suggest.suggest_action() {
             println!("Suggestion: {}",suggestion);
             if self.options().broker.is_some() {
-                println!("Getting next move with auto-retry from game broker...");
+                println!("Waiting for the next move from the game broker...");
             }
             loop {
                 match self.console_read_move() {
-                    Ok((from,to)) => {
-                        if self.console_human_play_turn_from_coords(from, to) {
-                            break;
-                        } else {
-                            println!("Invalid move!");
-                            println!();
+                    Ok(ConsoleCommand::Play(action)) => {
+                        match self.console_human_play_turn_from_action(action) {
+                            Ok(()) => break Ok(Some(action)),
+                            Err(reason) => {
+                                println!("Invalid move: {reason}!");
+                                println!();
+                            }
                         }
                     },
-                    Err(s) if s == "quit" || s == "exit" => {
-                        std::process::exit(0);
+                    Ok(ConsoleCommand::Invalid(error)) => {
+                        println!("Invalid move: {error}!");
+                        println!();
                     },
-                    Err(s) if s == "broker retry" => {
-                        // println!("Trying broker again in 100ms");
-                        std::thread::sleep(instant::Duration::from_millis(100));
+                    Ok(ConsoleCommand::Quit) => {
+                        std::process::exit(0);
                     },
-                    _ => {
+                    Err(error) => break Err(error),
+                    Ok(ConsoleCommand::Help) => {
                         println!();
                         println!("Enter source coordinates followed by target for action (move, attack, repair).");
+                        println!("Units with a range above 1 can attack from a distance (see the damage table).");
                         println!("If source=target it means self-destruct."); 
                         println!("example input: a6 d9"); 
+                        println!("Actions can also be explicit: M e3-d3 (move), A e3xd3 (attack), R a1+b1 (repair), S c2! (self-destruct) or pass.");
+                        if self.reinforcements().is_some() {
+                            println!("Enter spawn followed by a unit type and a coordinate next to your AI to spawn a unit (example: spawn virus c3 or N c3=virus).");
+                        }
+                        if self.action_points().is_some() {
+                            println!("Enter end to end your turn after at least one action.");
+                        }
                         println!();
                         println!("Damage table:");
                         let legend = Some("from / to");
                         let width = 10;
                         let tfmt = ToString::to_string;
                         Self::console_table(width, 
-                            UnitType::damage_table(legend,tfmt,tfmt));
+                            UnitType::damage_table(self.rules(),legend,tfmt,tfmt));
                         println!();
                         println!("Repair table:");
                         Self::console_table(width, 
-                            UnitType::repair_table(legend,tfmt,tfmt));
+                            UnitType::repair_table(self.rules(),legend,tfmt,tfmt));
                         println!();
-                        println!("Self destruct damage: {} per adjacent unit (including diagonals and firendlies)",UnitType::self_destruct_string());
+                        println!("Self destruct damage: {} per adjacent unit (including diagonals and firendlies)",self.rules().self_destruct_string());
                         println!();
                     }
                 }
             }
         } else {
             self.state.deadlock = true;
+            Ok(None)
         }
     }
     pub fn console_table(width: usize, table: Vec<Vec<String>>) {
@@ -103,19 +129,14 @@ impl Game {
             println!();
         }
     }
-    pub fn console_computer_play_turn(&mut self) {
+    // returns the action played (None if deadlocked), errors come from the broker
+    pub fn console_computer_play_turn(&mut self) -> Result<Option<Action>,WargameError> {
         let opt_action = self.computer_play_turn(Some(&mut stdout())).expect("no errors on stdout");
         stdout().flush().expect("no errors on stdout");
-        #[cfg(not(feature="broker"))]
-        let _ = opt_action;
         #[cfg(feature="broker")]
-        if self.options().broker.is_some() && opt_action.is_some() {
-            if let Some(coord_pair) = opt_action.unwrap().into_coord_pair() {
-                if let Err(error) = self.broker_post_move(coord_pair) {
-                    eprintln!("Could not post move to broker: {error}");
-                    std::process::exit(1);
-                }
-            }
+        if let (Some(_), Some(action)) = (&self.options().broker, opt_action) {
+            self.broker_post_move(action)?;
         }
+        Ok(opt_action)
     }
 }
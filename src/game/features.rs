@@ -0,0 +1,121 @@
+use crate::{Game, GameOptions, Player, UnitType, Coord, CoordPair, Dim};
+
+use std::io::Write as IoWrite;
+use std::io::{Error as IoError, ErrorKind, Result as IoResult};
+
+// number of virus distance slots per player (closest first, 0 when absent)
+pub const FEATURE_VIRUS_SLOTS : usize = 2;
+
+impl Game {
+    // numeric description of the position (for ML and analytics), laid out as in feature_names:
+    // unit counts by type, total health, Virus distances to the nearest enemy AI (closest first),
+    // engaged units and mobility, for each player, then the moves remaining (-1 without a limit)
+    // and the index of the next player
+    pub fn features(&self) -> Vec<f32> {
+        let mut features = Vec::new();
+        for &player in self.players() {
+            for unit_type in UnitType::all() {
+                features.push(self.unit_totals().count(player, unit_type) as f32);
+            }
+        }
+        for &player in self.players() {
+            features.push(self.unit_totals().total_health(player) as f32);
+        }
+        for &player in self.players() {
+            let mut distances = self.virus_distances(player);
+            distances.resize(FEATURE_VIRUS_SLOTS, 0);
+            features.extend(distances.into_iter().take(FEATURE_VIRUS_SLOTS).map(|d| d as f32));
+        }
+        for &player in self.players() {
+            features.push(self.player_unit_coords(player)
+                .filter(|(coord,_)| self.is_engaged(*coord))
+                .count() as f32);
+        }
+        for &player in self.players() {
+            features.push(self.mobility(player) as f32);
+        }
+        features.push(match self.options.max_moves {
+            Some(max_moves) => max_moves.saturating_sub(self.total_moves()) as f32,
+            None => -1.0,
+        });
+        features.push(self.player().index() as f32);
+        features
+    }
+    pub fn feature_names(players: &[Player]) -> Vec<String> {
+        let mut names = Vec::new();
+        for &player in players {
+            for unit_type in UnitType::all() {
+                names.push(format!("{player}_{unit_type}_count"));
+            }
+        }
+        for &player in players {
+            names.push(format!("{player}_health"));
+        }
+        for &player in players {
+            for slot in 0..FEATURE_VIRUS_SLOTS {
+                names.push(format!("{player}_Virus{slot}_ai_distance"));
+            }
+        }
+        for &player in players {
+            names.push(format!("{player}_engaged"));
+        }
+        for &player in players {
+            names.push(format!("{player}_mobility"));
+        }
+        names.push(String::from("moves_remaining"));
+        names.push(String::from("next_player"));
+        names
+    }
+    pub fn ai_coord(&self, player: Player) -> Option<Coord> {
+        self.unit_totals().ai_coord(player)
+    }
+    pub fn virus_distances(&self, player: Player) -> Vec<Dim> {
+        let enemy_ais = self.players().iter().copied()
+            .filter(|&p| !self.are_allies(p, player))
+            .filter_map(|p| self.ai_coord(p))
+            .collect::<Vec<_>>();
+        let mut distances = self.player_unit_coords(player)
+            .filter(|(_,cell)| cell.unit().expect("unit").unit_type == UnitType::Virus)
+            .filter_map(|(from,_)| enemy_ais.iter()
+                .map(|&to| CoordPair::new(from,to).moves_distance())
+                .min())
+            .collect::<Vec<_>>();
+        distances.sort();
+        distances
+    }
+    pub fn mobility(&self, player: Player) -> usize {
+        let mut game = self.clone();
+        game.state.player = player;
+        game.player_unit_coords(player)
+            .flat_map(|(coord,_)| game.possible_actions_from_coord(coord))
+            .count()
+    }
+    pub fn features_csv_header(w: &mut impl IoWrite, players: &[Player]) -> IoResult<()> {
+        writeln!(w,"game,move,{},result",Self::feature_names(players).join(","))
+    }
+    // plays computer vs computer games and writes a CSV row for each position, with the result of its game
+    // (a scenario that doesn't fit the rules is invalid input)
+    pub fn self_play_features_csv(options: GameOptions, games: usize, w: &mut impl IoWrite) -> IoResult<()> {
+        let initial = Game::try_new(options).map_err(|error| IoError::new(ErrorKind::InvalidInput, error))?;
+        // the players come from the scenario (or the classic setup)
+        Self::features_csv_header(w, initial.players())?;
+        for game_index in 0..games {
+            let mut game = initial.clone();
+            #[cfg(feature="stats")]
+            game.set_new_stats();
+            let mut rows = Vec::new();
+            let result = loop {
+                rows.push((game.total_moves(), game.features()));
+                if let Some(result) = game.end_game_result() {
+                    break result;
+                }
+                game.computer_play_turn(None::<&mut std::io::Sink>)?;
+            };
+            for (moves, features) in rows {
+                let features = features.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+                writeln!(w,"{game_index},{moves},{},{result}",features.join(","))?;
+            }
+        }
+        Ok(())
+    }
+}
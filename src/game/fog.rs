@@ -0,0 +1,154 @@
+use crate::{Game, Player, Coord, Dim, BoardCell, UnitType, Action, HeuristicScore, Terrain};
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+use instant::Instant;
+
+// what a player sees of the board under fog of war: terrain is always known,
+// units only within the visibility radius of one of the player's units
+#[derive(Debug, Clone)]
+pub struct Observation {
+    player: Player,
+    rows: Dim,
+    cols: Dim,
+    cells: Vec<BoardCell>,
+    visible: Vec<bool>,
+}
+
+impl Observation {
+    pub fn player(&self) -> Player {
+        self.player
+    }
+    pub fn rows(&self) -> Dim {
+        self.rows
+    }
+    pub fn cols(&self) -> Dim {
+        self.cols
+    }
+    fn index(&self, coord: Coord) -> Option<usize> {
+        let (row, col) = coord.to_tuple();
+        if row >= 0 && col >= 0 && row < self.rows && col < self.cols {
+            Some(row as usize * self.cols as usize + col as usize)
+        } else {
+            None
+        }
+    }
+    pub fn is_visible(&self, coord: Coord) -> bool {
+        self.index(coord).is_some_and(|index| self.visible[index])
+    }
+    // None if hidden or outside of the board
+    pub fn cell(&self, coord: Coord) -> Option<&BoardCell> {
+        self.index(coord).filter(|&index| self.visible[index]).map(|index| &self.cells[index])
+    }
+    pub fn terrain(&self, coord: Coord) -> Terrain {
+        self.index(coord).map(|index| self.cells[index].terrain()).unwrap_or_default()
+    }
+    pub fn hidden_coords(&self) -> impl Iterator<Item = Coord> + '_ {
+        let cols = self.cols as usize;
+        self.visible.iter().enumerate()
+            .filter(|(_, &visible)| !visible)
+            .map(move|(index, _)| Coord::new((index / cols) as Dim, (index % cols) as Dim))
+    }
+}
+
+impl Game {
+    // adjacent targets are always visible (minimum radius of 1), attacks at a distance
+    // need their target in sight (see action_from_coords)
+    pub fn fog_radius(&self) -> Option<Dim> {
+        self.options.fog_radius.map(|radius| radius.max(1))
+    }
+    // whose view of the board to show to the human players (fully once the game ends or without humans)
+    pub fn viewer_for(&self, humans: &[Player]) -> Option<Player> {
+        match humans {
+            _ if self.end_game_result().is_some() => None,
+            [] => None,
+            [human] => Some(*human),
+            _ => Some(self.player()),
+        }
+    }
+    // allies share what their units see
+    pub fn is_visible(&self, player: Player, coord: Coord) -> bool {
+        match self.fog_radius() {
+            None => true,
+            Some(radius) => self.players().iter()
+                .filter(|&&ally| self.are_allies(player, ally))
+                .any(|&ally| self.player_coords(ally).any(|unit_coord| unit_coord.is_in_range(coord, radius))),
+        }
+    }
+    pub fn observation(&self, player: Player) -> Observation {
+        let mut cells = Vec::new();
+        let mut visible = Vec::new();
+        for coord in self.rect_iter() {
+            let cell = self[coord];
+            let is_visible = self.is_visible(player, coord);
+            cells.push(if is_visible { cell } else { BoardCell::new_terrain(cell.terrain()) });
+            visible.push(is_visible);
+        }
+        Observation { player, rows: self.rows(), cols: self.cols(), cells, visible }
+    }
+    // a full information game consistent with what the observer sees: hidden units are
+    // placed at random on hidden cells (the number of units of each type is public but
+    // their health is not, so they start from full health)
+    pub fn determinize(&self, observer: Player, rng: &mut impl Rng) -> Self {
+        let observation = self.observation(observer);
+        let mut game = self.clone();
+        let mut options = game.clone_options();
+        options.fog_radius = None;
+        game.set_options(options);
+        let mut hidden_units : Vec<(Player,UnitType)> = Vec::new();
+        for coord in observation.hidden_coords() {
+            if let Some(cell) = game.remove_cell(coord) {
+                if let Some((&player, unit)) = cell.player_unit() {
+                    hidden_units.push((player, unit.unit_type));
+                }
+            }
+        }
+        let mut free_coords = observation.hidden_coords()
+            .filter(|&coord| game[coord].is_passable())
+            .collect::<Vec<_>>();
+        free_coords.shuffle(rng);
+        for ((player, unit_type), coord) in hidden_units.into_iter().zip(free_coords) {
+            let health = game.rules().initial_health(unit_type);
+            game.set_cell(coord, BoardCell::new_unit_with_health(player, unit_type, health));
+        }
+        game
+    }
+    // searches a number of determinized games (time is shared between them) and plays
+    // the action suggested most often (ties broken by the total score); suggestions
+    // that are not legal in the real game (attacks on units that are not there) are ignored
+    pub fn suggest_action_determinized(&mut self) -> (HeuristicScore, Option<Action>, f32, f32) {
+        let start_time = Instant::now();
+        let samples = self.options.fog_samples.max(1);
+        let mut rng = rand::thread_rng();
+        let mut votes : Vec<(Action, usize, HeuristicScore)> = Vec::new();
+        let mut total_depth = 0.0;
+        for _ in 0..samples {
+            let mut sample = self.determinize(self.player(), &mut rng);
+            let mut options = sample.clone_options();
+            options.max_seconds = options.max_seconds.map(|seconds| seconds / samples as f32);
+            sample.set_options(options);
+            let (score, suggestion, _, avg_depth) = sample.suggest_action();
+            total_depth += avg_depth;
+            if let Some(action) = suggestion.filter(|&action| self.is_legal_action(action)) {
+                match votes.iter_mut().find(|(voted, _, _)| *voted == action) {
+                    Some((_, count, total_score)) => {
+                        *count += 1;
+                        *total_score = total_score.saturating_add(score);
+                    },
+                    None => votes.push((action, 1, score)),
+                }
+            }
+        }
+        let best = votes.into_iter().max_by_key(|&(_, count, total_score)| (count, total_score));
+        let elapsed_seconds = Instant::now().duration_since(start_time).as_secs_f32();
+        let avg_depth = total_depth / samples as f32;
+        match best {
+            Some((action, count, total_score)) => (total_score / count as HeuristicScore, Some(action), elapsed_seconds, avg_depth),
+            None => {
+                // any legal action is better than a deadlock
+                let action = self.possible_actions().next();
+                (0, action, elapsed_seconds, avg_depth)
+            },
+        }
+    }
+}
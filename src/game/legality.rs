@@ -0,0 +1,140 @@
+use crate::{Game, Coord, Action, Terrain};
+
+// why an action can't be played (see Game::move_legality and Game::action_legality)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IllegalActionReason {
+    OffBoard,
+    NoUnit,
+    // the source unit belongs to another player
+    WrongPlayer,
+    // with action points per unit
+    NoActionsLeft,
+    OutOfRange,
+    // the destination of a move is a wall or another unit
+    Blocked,
+    Stunned,
+    Engaged,
+    // units holding a chokepoint can't leave it while engaged
+    HoldingChokepoint,
+    ForwardOnly,
+    NoTarget,
+    // ranged attacks need their target in sight (fog of war)
+    NotVisible,
+    AlliedUnit,
+    CantDamage,
+    // the explicit action is a repair but the target is an opposing unit
+    OpposingUnit,
+    // the explicit action is an attack but the target is a friendly unit
+    FriendlyUnit,
+    CantRepair,
+    FullHealth,
+    CantSpawn,
+    CantEndTurn,
+}
+
+impl std::fmt::Display for IllegalActionReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::OffBoard => "coordinates outside of the board",
+            Self::NoUnit => "no unit to act with",
+            Self::WrongPlayer => "the unit belongs to another player",
+            Self::NoActionsLeft => "the unit has no actions left this turn",
+            Self::OutOfRange => "the target is out of range",
+            Self::Blocked => "the destination is not free",
+            Self::Stunned => "the unit is stunned and can't move",
+            Self::Engaged => "the unit is engaged in combat and can't move",
+            Self::HoldingChokepoint => "the unit holds a chokepoint and can't leave it while engaged",
+            Self::ForwardOnly => "the unit can only move forward",
+            Self::NoTarget => "no unit to act on",
+            Self::NotVisible => "the target is not in sight",
+            Self::AlliedUnit => "can't act on allied units",
+            Self::CantDamage => "the unit can't damage its target",
+            Self::OpposingUnit => "can only repair friendly units",
+            Self::FriendlyUnit => "can't attack friendly units",
+            Self::CantRepair => "the unit can't repair its target",
+            Self::FullHealth => "the target is already at full health",
+            Self::CantSpawn => "can't spawn a unit there now",
+            Self::CantEndTurn => "the turn can only end after an action",
+        })
+    }
+}
+
+impl std::error::Error for IllegalActionReason {}
+
+impl Game {
+    // the source is a unit of the player to move that can still act this turn
+    pub fn source_legality(&self, from: Coord) -> Result<(),IllegalActionReason> {
+        if !self.is_valid_position(from) {
+            Err(IllegalActionReason::OffBoard)
+        } else if self[from].player().is_none() {
+            Err(IllegalActionReason::NoUnit)
+        } else if self[from].player() != Some(self.player()) {
+            Err(IllegalActionReason::WrongPlayer)
+        } else if !self.can_activate(from) {
+            Err(IllegalActionReason::NoActionsLeft)
+        } else {
+            Ok(())
+        }
+    }
+    pub fn move_legality(&self, from: Coord, to: Coord) -> Result<(),IllegalActionReason> {
+        self.source_legality(from)?;
+        let engaged = || self.is_engaged(from);
+        if !self.are_in_range(from, to, 1) {
+            Err(IllegalActionReason::OutOfRange)
+        } else if !self[to].is_passable() {
+            Err(IllegalActionReason::Blocked)
+        } else if self[from].unit().is_some_and(|unit| unit.is_stunned()) {
+            Err(IllegalActionReason::Stunned)
+        } else if !(self.options.move_while_engaged || self.can_move_while_engaged(from)
+            || (self.options.move_while_engaged_full_health && self.is_full_health(from))) && engaged()
+        {
+            Err(IllegalActionReason::Engaged)
+        } else if self.terrain(from) == Terrain::Chokepoint && engaged() {
+            Err(IllegalActionReason::HoldingChokepoint)
+        } else if self.options.move_only_forward && !self.can_move_back(from) && !self.is_moving_forward(from, to) {
+            Err(IllegalActionReason::ForwardOnly)
+        } else {
+            Ok(())
+        }
+    }
+    // both cells hold a unit and the target is within reach (see unit_combat and unit_repair)
+    pub(crate) fn target_legality(&self, from: Coord, to: Coord, in_range: impl FnOnce() -> bool) -> Result<(),IllegalActionReason> {
+        if !self.is_valid_position(from) || !self.is_valid_position(to) {
+            Err(IllegalActionReason::OffBoard)
+        } else if !self[from].is_unit() {
+            Err(IllegalActionReason::NoUnit)
+        } else if !self[to].is_unit() {
+            Err(IllegalActionReason::NoTarget)
+        } else if !in_range() {
+            Err(IllegalActionReason::OutOfRange)
+        } else {
+            Ok(())
+        }
+    }
+    // explicit actions are checked as written: a coordinate pair that means another action is rejected
+    pub fn action_legality(&self, action: Action) -> Result<(),IllegalActionReason> {
+        match action {
+            Action::Pass => Ok(()),
+            Action::EndTurn if self.can_end_turn() => Ok(()),
+            Action::EndTurn => Err(IllegalActionReason::CantEndTurn),
+            Action::Spawn { at, unit_type } if self.can_spawn(at, unit_type) => Ok(()),
+            Action::Spawn { .. } => Err(IllegalActionReason::CantSpawn),
+            Action::Move { from, to } => self.move_legality(from, to),
+            Action::SelfDestruct { from } => self.source_legality(from),
+            Action::Repair { from, to } | Action::Attack { from, to } => {
+                self.source_legality(from)?;
+                if !self.is_valid_position(to) {
+                    return Err(IllegalActionReason::OffBoard);
+                }
+                match (action, self[to].player()) {
+                    (_, None) => Err(IllegalActionReason::NoTarget),
+                    (Action::Attack { .. }, Some(target)) if target == self.player() => Err(IllegalActionReason::FriendlyUnit),
+                    (Action::Repair { .. }, Some(target)) if !self.are_allies(target, self.player()) => Err(IllegalActionReason::OpposingUnit),
+                    _ if self.action_from_coords(from, to)? == action => Ok(()),
+                    // a unit repairing itself
+                    _ => Err(IllegalActionReason::NoTarget),
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{Game, Player, Action, ActionOutcome, ParseActionError, WargameError};
+
+impl Game {
+    // an action in the explicit notation (see Display for Action), a coordinate pair ("a6 d9",
+    // the intent is inferred by action_from_coords) or the console spawn form ("spawn virus c3")
+    pub fn parse_action(&self, input: &str) -> Result<Action,WargameError> {
+        let input = input.trim();
+        // rows past Z are lowercase letters, otherwise coordinates can be typed in any case
+        let text = if self.rows() <= 26 { input.to_ascii_uppercase() } else { input.to_string() };
+        match text.parse::<Action>() {
+            Ok(action) => Ok(action),
+            Err(ParseActionError::UnknownKind(kind)) => {
+                if let Some(action) = Self::parse_spawn(input) {
+                    Ok(action)
+                } else if let Some((from, to)) = Self::parse_move(input) {
+                    Ok(self.action_from_coords(from, to)?)
+                } else {
+                    Err(ParseActionError::UnknownKind(kind).into())
+                }
+            },
+            Err(error) => Err(error.into()),
+        }
+    }
+    // plays the actions of a game record: one action per line in either form, '#' starts a comment
+    pub fn replay(&mut self, record: &str) -> Result<Vec<(Player,Action,ActionOutcome)>,WargameError> {
+        let mut played = Vec::new();
+        for (line_index, line) in record.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = |error: WargameError| WargameError::Record { line: line_index+1, error: Box::new(error) };
+            if self.end_game_result().is_some() {
+                return Err(error(WargameError::GameOver));
+            }
+            let action = self.parse_action(line).map_err(error)?;
+            self.action_legality(action).map_err(|reason| error(reason.into()))?;
+            played.push(self.play_turn_from_action(action).map_err(error)?);
+        }
+        Ok(played)
+    }
+}
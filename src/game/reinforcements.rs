@@ -0,0 +1,89 @@
+use crate::{Game, Coord, Player, UnitType, BoardCell, Action, ActionOutcome, WargameError, game::IllegalActionReason};
+
+use anyhow::anyhow;
+
+// reinforcement variant: the AI of a player can spawn new units next to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reinforcements {
+    // spawns of each player for the whole game
+    pub spawns: usize,
+    // turns of the player between two spawns (1 allows a spawn every turn)
+    pub cooldown: usize,
+}
+
+// "SPAWNS" or "SPAWNS:COOLDOWN" (cooldown of 1 by default)
+impl std::str::FromStr for Reinforcements {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (spawns, cooldown) = s.split_once(':').unwrap_or((s, "1"));
+        let spawns = spawns.parse::<usize>().map_err(|_| anyhow!("invalid number of spawns: {spawns}"))?;
+        let cooldown = cooldown.parse::<usize>().ok().filter(|&cooldown| cooldown >= 1)
+            .ok_or(anyhow!("invalid cooldown (minimum of 1): {cooldown}"))?;
+        Ok(Self { spawns, cooldown })
+    }
+}
+
+// reinforcements left to a player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Reserve {
+    pub spawns: usize,
+    // turns of the player before the next spawn
+    pub cooldown: usize,
+}
+
+impl Game {
+    pub fn reinforcements(&self) -> Option<Reinforcements> {
+        self.options.reinforcements
+    }
+    pub fn reserve(&self, player: Player) -> Reserve {
+        self.state.reserves[player.index() as usize]
+    }
+    // unit types that can be spawned (any but the AI)
+    pub fn spawnable_unit_types() -> impl Iterator<Item = UnitType> {
+        UnitType::all().filter(|&unit_type| unit_type != UnitType::AI)
+    }
+    pub fn can_spawn(&self, at: Coord, unit_type: UnitType) -> bool {
+        let reserve = self.reserve(self.player());
+        self.reinforcements().is_some() && reserve.spawns > 0 && reserve.cooldown == 0 &&
+            unit_type != UnitType::AI && self.is_valid_position(at) &&
+            self.state.totals.ai_coord(self.player()).is_some_and(|ai| self.are_in_range(ai, at, 1)) &&
+            self[at].is_passable()
+    }
+    pub fn possible_spawns(&self) -> impl Iterator<Item = Action> + '_ {
+        let ai = self.state.totals.ai_coord(self.player())
+            .filter(|_| self.reinforcements().is_some());
+        ai.into_iter()
+            .flat_map(|ai| ai.rect_around(1).rect_iter())
+            .flat_map(|at| Self::spawnable_unit_types().map(move |unit_type| Action::Spawn { at, unit_type }))
+            .filter(|&action| matches!(action, Action::Spawn { at, unit_type } if self.can_spawn(at, unit_type)))
+    }
+    pub fn unit_spawn(&mut self, at: Coord, unit_type: UnitType) -> Result<ActionOutcome,WargameError> {
+        if self.can_spawn(at, unit_type) {
+            let (player, cooldown) = (self.player(), self.options.reinforcements.map_or(1, |r| r.cooldown));
+            let health = self.rules().initial_health(unit_type);
+            self.set_cell(at, BoardCell::new_unit_with_health(player, unit_type, health));
+            let reserve = &mut self.state.reserves[player.index() as usize];
+            reserve.spawns -= 1;
+            reserve.cooldown = cooldown;
+            Ok(ActionOutcome::Spawned { unit_type })
+        } else {
+            Err(IllegalActionReason::CantSpawn.into())
+        }
+    }
+    // the cooldown of the player about to play goes down by a turn
+    pub(crate) fn reserve_tick(&mut self) {
+        let reserve = &mut self.state.reserves[self.state.player.index() as usize];
+        reserve.cooldown = reserve.cooldown.saturating_sub(1);
+    }
+    // "spawn TYPE COORD" as entered in the console (e.g. "spawn virus C2")
+    pub fn parse_spawn(input: &str) -> Option<Action> {
+        let mut words = input.split_whitespace();
+        if !words.next()?.eq_ignore_ascii_case("spawn") {
+            return None;
+        }
+        let name = words.next()?;
+        let unit_type = UnitType::all().find(|t| t.to_string().eq_ignore_ascii_case(name))?;
+        let at = words.next()?.to_ascii_uppercase().parse::<Coord>().ok()?;
+        words.next().is_none().then_some(Action::Spawn { at, unit_type })
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{Game, GameResult, Player};
+
+use std::sync::Arc;
+use std::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
+
+// what happens when the same position (board and player to move) occurs `count` times
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RepetitionRule {
+    #[default]
+    Off,
+    Draw { count: usize },
+    DefenderWins { count: usize },
+}
+
+impl RepetitionRule {
+    pub fn count(&self) -> Option<usize> {
+        match self {
+            Self::Off => None,
+            Self::Draw { count } | Self::DefenderWins { count } => Some(*count),
+        }
+    }
+}
+
+// "off", "draw" or "defender" (threefold by default), optionally followed by ":COUNT"
+impl std::str::FromStr for RepetitionRule {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, count) = match s.split_once(':') {
+            Some((name, count)) => (name, count.parse::<usize>().ok().filter(|&c| c >= 2)
+                .ok_or(anyhow::anyhow!("invalid repetition count (minimum of 2): {count}"))?),
+            None => (s, 3),
+        };
+        match name {
+            "off" => Ok(Self::Off),
+            "draw" => Ok(Self::Draw { count }),
+            "defender" => Ok(Self::DefenderWins { count }),
+            _ => Err(anyhow::anyhow!("unknown repetition rule: {name}")),
+        }
+    }
+}
+
+// positions played since the last action that was not a move (shared between the games of a search)
+#[derive(Debug)]
+pub struct PositionHistory {
+    hash: u64,
+    previous: Option<Arc<PositionHistory>>,
+}
+
+impl Game {
+    pub fn position_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.player().hash(&mut hasher);
+        for coord in self.rect_iter() {
+            self[coord].hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+    // attacks, repairs and self-destructs change health so earlier positions can't come back
+    pub(crate) fn record_position(&mut self, reversible: bool) {
+        let previous = if reversible { self.state.history.take() } else { None };
+        self.state.history = Some(Arc::new(PositionHistory { hash: self.position_hash(), previous }));
+    }
+    // number of times the current position was played (including now)
+    pub fn repetitions(&self) -> usize {
+        let Some(current) = &self.state.history else {
+            return 0;
+        };
+        let mut count = 0;
+        let mut node = Some(current);
+        while let Some(history) = node {
+            if history.hash == current.hash {
+                count += 1;
+            }
+            node = history.previous.as_ref();
+        }
+        count
+    }
+    pub fn repetition_result(&self) -> Option<GameResult> {
+        let rule = self.options.repetition;
+        if rule.count().is_some_and(|count| self.repetitions() >= count) {
+            match rule {
+                RepetitionRule::DefenderWins { .. } => Some(GameResult::Winner(Player::Defender)),
+                _ => Some(GameResult::Draw),
+            }
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{Game, Player};
+
+use anyhow::anyhow;
+
+// groups of allied players (players not listed play on their own)
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Teams(Vec<Vec<Player>>);
+
+impl Teams {
+    pub fn new(teams: Vec<Vec<Player>>) -> Self {
+        Self(teams)
+    }
+    pub fn team(&self, player: Player) -> Option<usize> {
+        self.0.iter().position(|team| team.contains(&player))
+    }
+    pub fn are_allies(&self, player1: Player, player2: Player) -> bool {
+        player1 == player2 || self.team(player1).is_some_and(|team| self.team(player2) == Some(team))
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &[Player]> {
+        self.0.iter().map(Vec::as_slice)
+    }
+}
+
+// teams separated by commas, players of a team by '+' (e.g. "attacker+raider,defender+invader")
+impl std::str::FromStr for Teams {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut teams : Vec<Vec<Player>> = Vec::new();
+        for team in s.split(',') {
+            let mut players = Vec::new();
+            for name in team.split('+') {
+                let player = Player::all()
+                    .find(|p| p.to_string().eq_ignore_ascii_case(name.trim()))
+                    .ok_or(anyhow!("unknown player: {name}"))?;
+                if players.contains(&player) || teams.iter().any(|team| team.contains(&player)) {
+                    return Err(anyhow!("{player} is in more than one team"));
+                }
+                players.push(player);
+            }
+            teams.push(players);
+        }
+        Ok(Self(teams))
+    }
+}
+
+impl std::fmt::Display for Teams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let teams = self.0.iter()
+            .map(|team| team.iter().map(|p| p.to_string().to_ascii_lowercase()).collect::<Vec<_>>().join("+"))
+            .collect::<Vec<_>>();
+        write!(f, "{}", teams.join(","))
+    }
+}
+
+impl Game {
+    // players of the game (turns follow Player::next, skipping the others)
+    pub fn players(&self) -> &[Player] {
+        &self.options.players
+    }
+    pub fn are_allies(&self, player1: Player, player2: Player) -> bool {
+        self.options.teams.are_allies(player1, player2)
+    }
+    pub fn active_players(&self) -> impl Iterator<Item = Player> + '_ {
+        self.players().iter().copied().filter(|&player| !self.is_eliminated(player))
+    }
+    // true if the active players are not all on the same side
+    pub fn has_opponents(&self) -> bool {
+        let mut active = self.active_players();
+        match active.next() {
+            Some(first) => active.any(|player| !self.are_allies(first, player)),
+            None => false,
+        }
+    }
+    // next player in turn order that is still in the game (eliminated players are skipped)
+    pub fn next_active_player(&self, player: Player) -> Player {
+        let mut next = player.next();
+        for _ in 0..Player::cardinality() {
+            if self.players().contains(&next) && !self.is_eliminated(next) {
+                return next;
+            }
+            next = next.next();
+        }
+        player.next()
+    }
+    // next opponent in turn order (wins when the current player is deadlocked)
+    pub fn next_opponent(&self, player: Player) -> Player {
+        let mut next = player.next();
+        for _ in 0..Player::cardinality() {
+            if self.players().contains(&next) && !self.is_eliminated(next) && !self.are_allies(player, next) {
+                return next;
+            }
+            next = next.next();
+        }
+        player.next()
+    }
+    // units left by eliminated players are removed while the game goes on
+    pub(crate) fn remove_eliminated(&mut self) {
+        if !self.has_opponents() {
+            return;
+        }
+        for player in self.players().to_vec() {
+            if self.is_eliminated(player) {
+                let coords = self.player_coords(player).collect::<Vec<_>>();
+                for coord in coords {
+                    self.remove_cell(coord);
+                }
+            }
+        }
+    }
+}
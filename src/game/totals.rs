@@ -0,0 +1,72 @@
+use crate::{Coord, BoardCell, Player, UnitType, Health, HeuristicScore};
+
+const PLAYERS : usize = Player::cardinality();
+const UNIT_TYPES : usize = UnitType::cardinality();
+
+// running aggregates of the units on the board (kept up to date by the game actions)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UnitTotals {
+    counts: [[usize;UNIT_TYPES];PLAYERS],
+    health: [[usize;UNIT_TYPES];PLAYERS],
+    ai_coords: [Option<Coord>;PLAYERS],
+}
+
+impl UnitTotals {
+    pub fn from_unit_coords<'a>(unit_coords: impl Iterator<Item=(Coord,&'a BoardCell)>) -> Self {
+        let mut totals = Self::default();
+        for (coord, cell) in unit_coords {
+            totals.add_cell(coord, cell);
+        }
+        totals
+    }
+    pub fn add_cell(&mut self, coord: Coord, cell: &BoardCell) {
+        if let Some((&player, unit)) = cell.player_unit() {
+            let (p, t) = (player.index() as usize, unit.unit_type.index());
+            self.counts[p][t] += 1;
+            self.health[p][t] += unit.health as usize;
+            if unit.unit_type == UnitType::AI {
+                self.ai_coords[p] = Some(coord);
+            }
+        }
+    }
+    pub fn remove_cell(&mut self, coord: Coord, cell: &BoardCell) {
+        if let Some((&player, unit)) = cell.player_unit() {
+            let (p, t) = (player.index() as usize, unit.unit_type.index());
+            self.counts[p][t] -= 1;
+            self.health[p][t] -= unit.health as usize;
+            if unit.unit_type == UnitType::AI && self.ai_coords[p] == Some(coord) {
+                self.ai_coords[p] = None;
+            }
+        }
+    }
+    pub fn add_health(&mut self, player: Player, unit_type: UnitType, amount: Health) {
+        self.health[player.index() as usize][unit_type.index()] += amount as usize;
+    }
+    pub fn remove_health(&mut self, player: Player, unit_type: UnitType, amount: Health) {
+        self.health[player.index() as usize][unit_type.index()] -= amount as usize;
+    }
+    pub fn count(&self, player: Player, unit_type: UnitType) -> usize {
+        self.counts[player.index() as usize][unit_type.index()]
+    }
+    pub fn health(&self, player: Player, unit_type: UnitType) -> usize {
+        self.health[player.index() as usize][unit_type.index()]
+    }
+    pub fn total_count(&self, player: Player) -> usize {
+        self.counts[player.index() as usize].iter().sum()
+    }
+    pub fn total_health(&self, player: Player) -> usize {
+        self.health[player.index() as usize].iter().sum()
+    }
+    pub fn ai_coord(&self, player: Player) -> Option<Coord> {
+        self.ai_coords[player.index() as usize]
+    }
+    pub fn has_ai(&self, player: Player) -> bool {
+        self.count(player, UnitType::AI) > 0
+    }
+    pub fn material(&self, player: Player, score_fn: fn(UnitType) -> HeuristicScore) -> HeuristicScore {
+        UnitType::all().map(|t| score_fn(t) * self.count(player, t) as HeuristicScore).sum()
+    }
+    pub fn weighted_health(&self, player: Player, score_fn: fn(UnitType) -> HeuristicScore) -> HeuristicScore {
+        UnitType::all().map(|t| score_fn(t) * self.health(player, t) as HeuristicScore).sum()
+    }
+}
@@ -0,0 +1,337 @@
+use crate::{Game, Coord, Player, UnitType, Action};
+
+use std::io::{stdout, Result as IoResult};
+use instant::Duration;
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::terminal::{enable_raw_mode, disable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::execute;
+use ratatui::{Frame, Terminal};
+use ratatui::backend::{Backend, CrosstermBackend};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Row, Table};
+
+// keys of the terminal interface (see tui_input)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TuiInput {
+    Up,
+    Down,
+    Left,
+    Right,
+    // selects the unit under the cursor, or plays the action of the selected unit on the cursor
+    Select,
+    Cancel,
+    Pass,
+    EndTurn,
+    // spawns a unit of the chosen type under the cursor (reinforcements)
+    Spawn,
+    NextSpawnType,
+    ScrollUp,
+    ScrollDown,
+    // switches the side panel between the game info and the damage/repair tables
+    ToggleTables,
+    Quit,
+}
+
+pub fn tui_input(key: KeyEvent) -> Option<TuiInput> {
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => Some(TuiInput::Up),
+        KeyCode::Down | KeyCode::Char('j') => Some(TuiInput::Down),
+        KeyCode::Left | KeyCode::Char('h') => Some(TuiInput::Left),
+        KeyCode::Right | KeyCode::Char('l') => Some(TuiInput::Right),
+        KeyCode::Enter | KeyCode::Char(' ') => Some(TuiInput::Select),
+        KeyCode::Esc => Some(TuiInput::Cancel),
+        KeyCode::Char('p') => Some(TuiInput::Pass),
+        KeyCode::Char('e') => Some(TuiInput::EndTurn),
+        KeyCode::Char('n') => Some(TuiInput::Spawn),
+        KeyCode::Tab => Some(TuiInput::NextSpawnType),
+        KeyCode::PageUp => Some(TuiInput::ScrollUp),
+        KeyCode::PageDown => Some(TuiInput::ScrollDown),
+        KeyCode::Char('t') => Some(TuiInput::ToggleTables),
+        KeyCode::Char('q') => Some(TuiInput::Quit),
+        _ => None,
+    }
+}
+
+// what the interface shows besides the game itself
+#[derive(Debug, Clone, Default)]
+pub struct TuiState {
+    pub cursor: Coord,
+    // unit whose legal targets are highlighted
+    pub selected: Option<Coord>,
+    pub spawn_type: UnitType,
+    pub log: Vec<String>,
+    // lines scrolled back from the end of the log
+    pub log_scroll: usize,
+    pub show_tables: bool,
+    // last error or notice, shown under the board
+    pub message: Option<String>,
+}
+
+impl TuiState {
+    pub fn new(game: &Game) -> Self {
+        Self {
+            cursor: Coord::new(game.rows()/2, game.cols()/2),
+            spawn_type: Game::spawnable_unit_types().next().unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+    // lines written by human_play_turn_from_action and computer_play_turn
+    pub fn log_output(&mut self, output: &[u8]) {
+        let output = String::from_utf8_lossy(output);
+        self.log.extend(output.lines().filter(|line| !line.is_empty()).map(String::from));
+        self.log_scroll = 0;
+    }
+}
+
+// the cell an action is shown on
+fn action_target(action: Action) -> Option<Coord> {
+    match action {
+        Action::Move { to, .. } | Action::Attack { to, .. } | Action::Repair { to, .. } => Some(to),
+        Action::SelfDestruct { from } => Some(from),
+        Action::Spawn { at, .. } => Some(at),
+        Action::Pass | Action::EndTurn => None,
+    }
+}
+
+fn action_color(action: Action) -> Color {
+    match action {
+        Action::Move { .. } => Color::Green,
+        Action::Attack { .. } => Color::Red,
+        Action::Repair { .. } => Color::Blue,
+        Action::SelfDestruct { .. } => Color::Magenta,
+        Action::Spawn { .. } | Action::Pass | Action::EndTurn => Color::Yellow,
+    }
+}
+
+fn player_color(player: Player) -> Color {
+    match player {
+        Player::Attacker => Color::LightBlue,
+        Player::Defender => Color::LightRed,
+        Player::Raider => Color::LightGreen,
+        Player::Invader => Color::LightMagenta,
+    }
+}
+
+impl Game {
+    // legal actions of the selected unit (or of the unit under the cursor)
+    pub fn tui_targets(&self, state: &TuiState) -> Vec<Action> {
+        let source = state.selected.unwrap_or(state.cursor);
+        if self.source_legality(source).is_ok() {
+            self.possible_actions_from_coord(source).collect()
+        } else {
+            Vec::new()
+        }
+    }
+    // updates the interface and returns the action chosen by the player, if any
+    pub fn tui_handle_input(&self, state: &mut TuiState, input: TuiInput) -> Option<Action> {
+        state.message = None;
+        let (row, col) = state.cursor.to_tuple();
+        match input {
+            TuiInput::Up => state.cursor = Coord::new((row-1).max(0), col),
+            TuiInput::Down => state.cursor = Coord::new((row+1).min(self.rows()-1), col),
+            TuiInput::Left => state.cursor = Coord::new(row, (col-1).max(0)),
+            TuiInput::Right => state.cursor = Coord::new(row, (col+1).min(self.cols()-1)),
+            TuiInput::Select => match state.selected {
+                Some(from) => {
+                    let targets = self.tui_targets(state);
+                    if let Some(&action) = targets.iter().find(|&&action| action_target(action) == Some(state.cursor)) {
+                        state.selected = None;
+                        return Some(action);
+                    } else if self.source_legality(state.cursor).is_ok() {
+                        state.selected = Some(state.cursor);
+                    } else if let Err(reason) = self.action_from_coords(from, state.cursor) {
+                        state.message = Some(format!("Invalid move: {reason}!"));
+                    }
+                },
+                None => match self.source_legality(state.cursor) {
+                    Ok(()) => state.selected = Some(state.cursor),
+                    Err(reason) => state.message = Some(format!("Invalid move: {reason}!")),
+                },
+            },
+            TuiInput::Cancel => state.selected = None,
+            TuiInput::Pass => return Some(Action::Pass),
+            TuiInput::EndTurn => return Some(Action::EndTurn),
+            TuiInput::Spawn => return Some(Action::Spawn { at: state.cursor, unit_type: state.spawn_type }),
+            TuiInput::NextSpawnType => {
+                let unit_types = Self::spawnable_unit_types().collect::<Vec<_>>();
+                let index = unit_types.iter().position(|&unit_type| unit_type == state.spawn_type).unwrap_or_default();
+                state.spawn_type = unit_types[(index + 1) % unit_types.len()];
+            },
+            TuiInput::ScrollUp => state.log_scroll = (state.log_scroll + 5).min(state.log.len().saturating_sub(1)),
+            TuiInput::ScrollDown => state.log_scroll = state.log_scroll.saturating_sub(5),
+            TuiInput::ToggleTables => state.show_tables = !state.show_tables,
+            TuiInput::Quit => {},
+        }
+        None
+    }
+    fn tui_board_lines(&self, state: &TuiState, viewer: Option<Player>) -> Vec<Line<'static>> {
+        let observation = viewer.map(|player| self.observation(player));
+        let targets = self.tui_targets(state);
+        // status effects make some cells wider
+        let width = self.rect_iter().map(|coord| self[coord].to_pretty_compact_string().len()).max().unwrap_or(3);
+        let mut lines = Vec::new();
+        let header = (0..self.cols()).map(|col| format!(" {:^width$}",col)).collect::<String>();
+        lines.push(Line::from(format!("    {header}")));
+        for row in 0..self.rows() {
+            let mut spans = vec![Span::raw(format!("{:>2}: ",(row as u8 + b'A') as char))];
+            for col in 0..self.cols() {
+                let coord = Coord::new(row,col);
+                let cell = self[coord];
+                let marker = cell.terrain().marker().unwrap_or(' ');
+                let hidden = observation.as_ref().is_some_and(|o| !o.is_visible(coord)) && cell.terrain().is_passable();
+                let text = if hidden { String::from(" ~ ") } else { cell.to_pretty_compact_string() };
+                let mut style = Style::default();
+                if let Some(player) = cell.player().filter(|_| !hidden) {
+                    style = style.fg(player_color(player));
+                }
+                if let Some(&action) = targets.iter().find(|&&action| action_target(action) == Some(coord)) {
+                    style = style.bg(action_color(action)).fg(Color::Black);
+                }
+                if state.selected == Some(coord) {
+                    style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                }
+                if state.cursor == coord {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                spans.push(Span::raw(marker.to_string()));
+                spans.push(Span::styled(format!("{text:<width$}"), style));
+            }
+            lines.push(Line::from(spans));
+        }
+        lines
+    }
+    fn tui_info_lines(&self, state: &TuiState) -> Vec<Line<'static>> {
+        let mut info = Vec::new();
+        self.pretty_print_info(&mut info).expect("no errors writing to a vector");
+        let mut lines = String::from_utf8_lossy(&info).lines().map(|line| Line::from(line.to_string())).collect::<Vec<_>>();
+        if self.reinforcements().is_some() {
+            lines.push(Line::from(format!("Spawn type: {} (tab to change, n to spawn)", state.spawn_type)));
+        }
+        if let Some(unit) = self.get_cell(state.cursor).and_then(|cell| cell.unit()) {
+            let player = self[state.cursor].player().expect("cell not empty");
+            lines.push(Line::from(""));
+            lines.push(Line::from(format!("{}: {} {} {}/{}", state.cursor, player, unit.unit_type, unit.health, self.rules().initial_health(unit.unit_type))));
+            if !unit.effects().is_empty() {
+                lines.push(Line::from(format!("effects: {}", unit.effects())));
+            }
+        }
+        lines
+    }
+    fn tui_table(&self, table: Vec<Vec<String>>, title: &'static str) -> Table<'static> {
+        let columns = table.first().map_or(0, Vec::len);
+        let mut rows = table.into_iter().map(Row::new);
+        let header = rows.next().unwrap_or_default().style(Style::default().add_modifier(Modifier::BOLD));
+        let widths = (0..columns).map(|index| Constraint::Length(if index == 0 { 10 } else { 8 }));
+        Table::new(rows, widths)
+            .header(header)
+            .block(Block::default().borders(Borders::ALL).title(title))
+    }
+    // the whole screen: board and message on the left, info (or tables) and move log on the right
+    pub fn tui_draw(&self, frame: &mut Frame, state: &TuiState, viewer: Option<Player>) {
+        let board = self.tui_board_lines(state, viewer);
+        let board_width = board.iter().map(Line::width).max().unwrap_or_default() as u16 + 2;
+        let [main, help] = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.size());
+        let [left, right] = Layout::horizontal([Constraint::Length(board_width.max(30)), Constraint::Min(0)]).areas(main);
+        let [board_area, message_area] = Layout::vertical([Constraint::Length(board.len() as u16 + 2), Constraint::Min(0)]).areas(left);
+        let [panel_area, log_area] = Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(right);
+
+        let title = match self.end_game_message() {
+            Some(_) => String::from(" Game over "),
+            None => format!(" {} to play ", self.player()),
+        };
+        frame.render_widget(Paragraph::new(board).block(Block::default().borders(Borders::ALL).title(title)), board_area);
+
+        let message = state.message.clone().or_else(|| self.end_game_message()).unwrap_or_default();
+        frame.render_widget(Paragraph::new(message).wrap(ratatui::widgets::Wrap { trim: true }), message_area);
+
+        if state.show_tables {
+            let [damage_area, repair_area] = Layout::vertical([Constraint::Percentage(55), Constraint::Percentage(45)]).areas(panel_area);
+            let legend = Some("from / to");
+            let tfmt = ToString::to_string;
+            frame.render_widget(self.tui_table(UnitType::damage_table(self.rules(),legend,tfmt,tfmt), " Damage "), damage_area);
+            frame.render_widget(self.tui_table(UnitType::repair_table(self.rules(),legend,tfmt,tfmt), " Repair "), repair_area);
+        } else {
+            let info = Paragraph::new(self.tui_info_lines(state)).block(Block::default().borders(Borders::ALL).title(" Info "));
+            frame.render_widget(info, panel_area);
+        }
+
+        // the end of the log is shown unless scrolled back
+        let visible = log_area.height.saturating_sub(2) as usize;
+        let end = state.log.len().saturating_sub(state.log_scroll);
+        let log = state.log[end.saturating_sub(visible)..end].iter().map(|line| Line::from(line.clone())).collect::<Vec<_>>();
+        frame.render_widget(Paragraph::new(log).block(Block::default().borders(Borders::ALL).title(" Moves ")), log_area);
+
+        let keys = "arrows: move  enter: select/act  esc: cancel  p: pass  e: end turn  t: tables  pgup/pgdn: log  q: quit";
+        frame.render_widget(Paragraph::new(keys).style(Style::default().add_modifier(Modifier::DIM)), help);
+    }
+    fn tui_loop(&mut self, terminal: &mut Terminal<impl Backend>, humans: &[Player], mut on_action: impl FnMut(Action) -> IoResult<()>) -> IoResult<()> {
+        let mut state = TuiState::new(self);
+        loop {
+            let computer_turn = self.end_game_result().is_none() && !humans.contains(&self.player());
+            if computer_turn {
+                state.message = Some(format!("{} is thinking...", self.player()));
+            }
+            let viewer = self.viewer_for(humans);
+            terminal.draw(|frame| self.tui_draw(frame, &state, viewer))?;
+            if computer_turn {
+                let mut output = Vec::new();
+                let action = self.computer_play_turn(Some(&mut output))?;
+                state.log_output(&output);
+                state.message = None;
+                if let Some(action) = action {
+                    on_action(action)?;
+                }
+                // quitting is possible between two computer turns
+                if event::poll(Duration::ZERO)? {
+                    if let Event::Key(key) = event::read()? {
+                        if tui_input(key) == Some(TuiInput::Quit) {
+                            return Ok(());
+                        }
+                    }
+                }
+                continue;
+            }
+            if self.end_game_result().is_none() && self.possible_actions().next().is_none() {
+                self.set_deadlock(true);
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match tui_input(key) {
+                Some(TuiInput::Quit) => return Ok(()),
+                Some(input) => {
+                    let action = self.tui_handle_input(&mut state, input);
+                    if let Some(action) = action.filter(|_| self.end_game_result().is_none()) {
+                        let mut output = Vec::new();
+                        match self.human_play_turn_from_action(Some(&mut output), action)? {
+                            Ok(()) => {
+                                state.log_output(&output);
+                                on_action(action)?;
+                            },
+                            Err(reason) => state.message = Some(format!("Invalid move: {reason}!")),
+                        }
+                    }
+                },
+                None => {},
+            }
+        }
+    }
+    // plays the game in a full-screen terminal: humans pick their actions with the cursor,
+    // the computer plays the other players (on_action gets every action played)
+    pub fn tui_play(&mut self, humans: &[Player], on_action: impl FnMut(Action) -> IoResult<()>) -> IoResult<()> {
+        enable_raw_mode()?;
+        execute!(stdout(), EnterAlternateScreen)?;
+        let result = Terminal::new(CrosstermBackend::new(stdout()))
+            .and_then(|mut terminal| self.tui_loop(&mut terminal, humans, on_action));
+        disable_raw_mode()?;
+        execute!(stdout(), LeaveAlternateScreen)?;
+        result
+    }
+}
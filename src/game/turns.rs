@@ -0,0 +1,112 @@
+use crate::{Game, Coord, Action};
+
+use anyhow::anyhow;
+
+// action points variant: a turn is a sequence of actions closed by Action::EndTurn
+// (or automatically once the budget is spent)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionPoints {
+    // number of actions per turn, with any units
+    PerTurn(usize),
+    // number of actions per unit and per turn
+    PerUnit(usize),
+}
+
+// "turn:K" or "unit:K"
+impl std::str::FromStr for ActionPoints {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, count) = s.split_once(':').ok_or(anyhow!("expected turn:K or unit:K: {s}"))?;
+        let count = count.parse::<usize>().ok().filter(|&count| count >= 1)
+            .ok_or(anyhow!("invalid number of action points (minimum of 1): {count}"))?;
+        match kind {
+            "turn" => Ok(Self::PerTurn(count)),
+            "unit" => Ok(Self::PerUnit(count)),
+            _ => Err(anyhow!("unknown action points kind: {kind}")),
+        }
+    }
+}
+
+// progress of the current turn
+#[derive(Debug, Clone, Default)]
+pub struct TurnPhase {
+    actions: usize,
+    // actions of each unit that acted this turn (by its current coordinates)
+    activations: Vec<(Coord, usize)>,
+    // an attack, repair or self-destruct was played this turn (see record_position)
+    irreversible: bool,
+}
+
+impl TurnPhase {
+    // actions played so far this turn
+    pub fn actions(&self) -> usize {
+        self.actions
+    }
+    pub fn activations(&self, coord: Coord) -> usize {
+        self.activations.iter()
+            .find(|(unit_coord, _)| *unit_coord == coord)
+            .map_or(0, |&(_, count)| count)
+    }
+    pub fn is_irreversible(&self) -> bool {
+        self.irreversible
+    }
+}
+
+impl Game {
+    pub fn action_points(&self) -> Option<ActionPoints> {
+        self.options.action_points
+    }
+    pub fn turn_phase(&self) -> &TurnPhase {
+        &self.state.phase
+    }
+    // None when only the units' budgets limit the turn
+    pub fn actions_left(&self) -> Option<usize> {
+        let played = self.state.phase.actions;
+        match self.action_points() {
+            None => Some(1usize.saturating_sub(played)),
+            Some(ActionPoints::PerTurn(count)) => Some(count.saturating_sub(played)),
+            Some(ActionPoints::PerUnit(_)) => None,
+        }
+    }
+    // the unit at coord still has actions left this turn
+    pub fn can_activate(&self, coord: Coord) -> bool {
+        match self.action_points() {
+            Some(ActionPoints::PerUnit(count)) => self.state.phase.activations(coord) < count,
+            _ => true,
+        }
+    }
+    // turns can be ended early once an action was played
+    pub fn can_end_turn(&self) -> bool {
+        self.action_points().is_some() && self.state.phase.actions > 0
+    }
+    // records the action in the turn phase and returns true if the turn is over
+    pub(crate) fn end_of_action(&mut self, action: Action) -> bool {
+        if action == Action::EndTurn {
+            return true;
+        }
+        let phase = &mut self.state.phase;
+        phase.actions += 1;
+        phase.irreversible |= !matches!(action, Action::Move { .. } | Action::Pass);
+        if let Some(coords) = action.into_coord_pair() {
+            let count = phase.activations(coords.from) + 1;
+            phase.activations.retain(|(coord, _)| *coord != coords.from);
+            // units that moved carry their activations along
+            let coord = if let Action::Move { to, .. } = action { to } else { coords.from };
+            phase.activations.push((coord, count));
+        }
+        // spawned units wait for the next turn
+        if let Action::Spawn { at, .. } = action {
+            phase.activations.push((at, usize::MAX));
+        }
+        // units destroyed during the turn leave their cell to others
+        let player = self.player();
+        let activations = std::mem::take(&mut self.state.phase.activations);
+        self.state.phase.activations = activations.into_iter()
+            .filter(|(coord, _)| self[*coord].player() == Some(player))
+            .collect();
+        self.actions_left() == Some(0)
+            || self.is_eliminated(self.player())
+            || self.end_game_result().is_some()
+            || self.possible_unit_actions().next().is_none()
+    }
+}
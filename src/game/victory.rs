@@ -0,0 +1,154 @@
+use crate::{Game, GameResult, Player, Coord, CoordPair};
+
+use anyhow::anyhow;
+
+// when a player is out of the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Elimination {
+    // losing the AI (classic rules)
+    #[default]
+    Ai,
+    // losing every unit
+    AllUnits,
+}
+
+// result when the maximum number of moves is reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MoveCapRule {
+    // the tie-break result (classic rules: the defender wins)
+    #[default]
+    TieBreak,
+    // the side with the most total health left wins (tie-break if equal)
+    Material,
+}
+
+// a player holding the zone at the start of their turn (with no opposing unit in it) wins
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hill {
+    pub zone: CoordPair,
+}
+
+impl Hill {
+    pub fn contains(&self, coord: Coord) -> bool {
+        let (from, to) = (self.zone.from, self.zone.to);
+        coord.row >= from.row.min(to.row) && coord.row <= from.row.max(to.row) &&
+        coord.col >= from.col.min(to.col) && coord.col <= from.col.max(to.col)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VictoryConditions {
+    pub elimination: Elimination,
+    pub move_cap: MoveCapRule,
+    pub hill: Option<Hill>,
+    // result when nothing else decides the game (all players eliminated at once, equal material...)
+    pub tie_break: GameResult,
+}
+
+impl Default for VictoryConditions {
+    fn default() -> Self {
+        Self {
+            elimination: Default::default(),
+            move_cap: Default::default(),
+            hill: None,
+            tie_break: GameResult::Winner(Player::Defender),
+        }
+    }
+}
+
+impl std::str::FromStr for Elimination {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ai" => Ok(Self::Ai),
+            "all-units" => Ok(Self::AllUnits),
+            _ => Err(anyhow!("unknown elimination rule: {s}")),
+        }
+    }
+}
+
+impl std::str::FromStr for MoveCapRule {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tie-break" => Ok(Self::TieBreak),
+            "material" => Ok(Self::Material),
+            _ => Err(anyhow!("unknown move cap rule: {s}")),
+        }
+    }
+}
+
+// a single cell ("C2") or a rectangle given by two corners ("B1-D3")
+impl std::str::FromStr for Hill {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (from, to) = s.split_once('-').unwrap_or((s, s));
+        Ok(Self { zone: CoordPair::new(from.parse::<Coord>()?, to.parse::<Coord>()?) })
+    }
+}
+
+// "attacker", "defender" or "draw"
+pub fn parse_tie_break(s: &str) -> Result<GameResult,anyhow::Error> {
+    match s {
+        "draw" => Ok(GameResult::Draw),
+        _ => Player::all()
+            .find(|p| p.to_string().eq_ignore_ascii_case(s))
+            .map(GameResult::Winner)
+            .ok_or(anyhow!("unknown tie-break: {s}")),
+    }
+}
+
+impl Game {
+    pub fn victory(&self) -> &VictoryConditions {
+        &self.options.victory
+    }
+    // announcement of the end of the game ("Attacker wins in 12 moves!", allies included)
+    pub fn end_game_message(&self) -> Option<String> {
+        match self.end_game_result()? {
+            GameResult::Winner(winner) => {
+                let allies = self.players().iter()
+                    .filter(|&&player| player != winner && self.are_allies(winner, player))
+                    .map(|player| format!(" and {player}"))
+                    .collect::<String>();
+                Some(format!("{}{} win{} in {} moves!", winner, allies, if allies.is_empty() { "s" } else { "" }, self.total_moves()))
+            },
+            GameResult::Draw => Some(format!("Draw after {} moves!", self.total_moves())),
+        }
+    }
+    pub fn is_eliminated(&self, player: Player) -> bool {
+        match self.victory().elimination {
+            Elimination::Ai => !self.state.totals.has_ai(player),
+            Elimination::AllUnits => self.state.totals.total_count(player) == 0,
+        }
+    }
+    pub fn move_cap_result(&self) -> GameResult {
+        let victory = self.victory();
+        match victory.move_cap {
+            MoveCapRule::TieBreak => victory.tie_break,
+            MoveCapRule::Material => {
+                // total health of each side (a player and their allies)
+                let health = |player| self.players().iter()
+                    .filter(|&&ally| self.are_allies(player, ally))
+                    .map(|&ally| self.state.totals.total_health(ally))
+                    .sum::<usize>();
+                let mut sides = self.active_players().map(|player| (health(player), player)).collect::<Vec<_>>();
+                sides.sort_by_key(|&(health, _)| std::cmp::Reverse(health));
+                match sides.as_slice() {
+                    [(best, winner), rest @ ..] if rest.iter().all(|&(health, player)| health < *best || self.are_allies(player, *winner)) =>
+                        GameResult::Winner(*winner),
+                    _ => victory.tie_break,
+                }
+            },
+        }
+    }
+    pub fn hill_result(&self) -> Option<GameResult> {
+        let hill = self.victory().hill?;
+        let holds = |player| self.player_coords(player).any(|coord| hill.contains(coord));
+        let player = self.player();
+        if holds(player) && !self.players().iter().any(|&other| !self.are_allies(player, other) && holds(other)) {
+            Some(GameResult::Winner(player))
+        } else {
+            None
+        }
+    }
+}
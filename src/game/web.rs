@@ -1,8 +1,8 @@
 use std::fmt::Write as FmtWrite;
 use std::fmt::Result as FmtResult;
 
-use crate::UnitType;
-use crate::{Coord, Game};
+use crate::{UnitType, Unit};
+use crate::{Coord, Game, Player};
 
 impl Game {
     pub fn to_html_board_string(&self, css_class: String, id: String, fn_click: String) -> String {
@@ -10,12 +10,23 @@ impl Game {
         self.to_html_board_writer(&mut html, css_class, id, fn_click).expect("write to string can't fail");
         html
     }
+    pub fn to_html_board_string_for(&self, css_class: String, id: String, fn_click: String, viewer: Option<Player>) -> String {
+        let mut html = String::new();
+        self.to_html_board_writer_for(&mut html, css_class, id, fn_click, viewer).expect("write to string can't fail");
+        html
+    }
     pub fn to_html_board_writer(&self, w : &mut impl FmtWrite, css_class: String, id: String, fn_click: String) -> FmtResult {
-        let half = (self.dim()+1) / 2;
+        self.to_html_board_writer_for(w, css_class, id, fn_click, None)
+    }
+    // board as seen by the viewer under fog of war (hidden cells get the "hidden" class)
+    pub fn to_html_board_writer_for(&self, w : &mut impl FmtWrite, css_class: String, id: String, fn_click: String, viewer: Option<Player>) -> FmtResult {
+        let observation = viewer.map(|player| self.observation(player));
+        // the two info headers span the row names and all the columns
+        let (left, right) = ((self.cols()+2) / 2, (self.cols()+1) / 2);
         write!(w,"<table id=\"{id}\" class=\"{css_class}\">")?;
         write!(w,"<thead>")?;
         write!(w,"<tr>")?;
-        write!(w,"<th colspan={half} class=\"{css_class}_info\">")?;
+        write!(w,"<th colspan={left} class=\"{css_class}_info\">")?;
         write!(w,"<div class=\"{css_class}_info_moves\">")?;
         if let Some(max_moves) = self.options.max_moves {
             if self.total_moves() >= max_moves {
@@ -28,27 +39,34 @@ impl Game {
         }
         write!(w,"</div>")?;
         write!(w,"</th>")?;
-        write!(w,"<th colspan={half} class=\"{css_class}_info\">")?;
+        write!(w,"<th colspan={right} class=\"{css_class}_info\">")?;
         write!(w,"<div class=\"{css_class}_info_next\">")?;
         write!(w,"Next player: {}",self.player())?;
+        if self.reinforcements().is_some() {
+            write!(w," ({} spawns left)",self.reserve(self.player()).spawns)?;
+        }
         write!(w,"</div>")?;
         write!(w,"</th>")?;
         write!(w,"</tr>")?;
         write!(w,"<tr>")?;
         write!(w,"<th class=\"{css_class}_row_name\"></th>")?;
-        for col in 0..self.dim() {
+        for col in 0..self.cols() {
             write!(w,"<th class=\"{css_class}_col_name\">{}</th>",col)?;
         }
         write!(w,"</tr>")?;
         write!(w,"</thead>")?;
         write!(w,"<tbody>")?;
-        for row in 0..self.dim() {
+        for row in 0..self.rows() {
             write!(w,"<tr>")?;
             write!(w,"<th class=\"{}_row_name\">{}</th>",css_class,(row as u8 + b'A') as char)?;
-            for col in 0..self.dim() {
-                let cell = self[Coord::new(row,col)];
-                write!(w,"<td id=\"{id}-{row}-{col}\" class=\"{css_class}_cell\" onclick=\"{fn_click}({row},{col})\">")?;
-                if !cell.is_empty() {
+            for col in 0..self.cols() {
+                let coord = Coord::new(row,col);
+                let cell = self[coord];
+                let terrain = cell.terrain();
+                let hidden = observation.as_ref().is_some_and(|o| !o.is_visible(coord));
+                let hidden_class = if hidden { format!(" {css_class}_hidden") } else { String::new() };
+                write!(w,"<td id=\"{id}-{row}-{col}\" class=\"{css_class}_cell {css_class}_{terrain}{hidden_class}\" title=\"{terrain}\" onclick=\"{fn_click}({row},{col})\">")?;
+                if !cell.is_empty() && !hidden {
                     let player = cell.player().expect("cell not empty");
                     let unit = cell.unit().expect("cell not empty");
                     let health = unit.health;
@@ -61,6 +79,10 @@ impl Game {
                     write!(w,"<span>:</span>")?;
                     write!(w,"<span>{health}</span>")?;
                     write!(w,"</div>")?;
+                    let effects = self.effect_names(unit);
+                    if !effects.is_empty() {
+                        write!(w,"<div class=\"{css_class}_effects\">{}</div>",effects.join(" "))?;
+                    }
                     write!(w,"</div>")?;
                 };
                 write!(w,"</td>")?;
@@ -70,6 +92,24 @@ impl Game {
         write!(w,"</tbody>")?;
         write!(w,"</table>")
     }
+    // short names of the status effects of a unit (with the turns left)
+    fn effect_names(&self, unit: &Unit) -> Vec<String> {
+        let effects = unit.effects();
+        let mut names = Vec::new();
+        if unit.has_shield(self.rules()) {
+            names.push(String::from("shield"));
+        }
+        if let Some((_, turns)) = effects.infection() {
+            names.push(format!("infected:{turns}"));
+        }
+        if effects.stun() > 0 {
+            names.push(format!("stunned:{}", effects.stun()));
+        }
+        if effects.shield_recharge() > 0 {
+            names.push(format!("recharging:{}", effects.shield_recharge()));
+        }
+        names
+    }
     fn html_th(s : &String) -> String {
         format!("<th>{s}</th>")
     }
@@ -79,14 +119,14 @@ impl Game {
     pub fn html_damage_table_string(&self, legend: Option<&str>) -> String {
         let mut html = String::new();
         Self::html_table_writer(&mut html,
-            UnitType::damage_table(legend,Self::html_th,Self::html_td))
+            UnitType::damage_table(self.rules(),legend,Self::html_th,Self::html_td))
             .expect("write to string can't fail");
         html
     }
     pub fn html_repair_table_string(&self, legend: Option<&str>) -> String {
         let mut html = String::new();
         Self::html_table_writer(&mut html,
-            UnitType::repair_table(legend,Self::html_th,Self::html_td))
+            UnitType::repair_table(self.rules(),legend,Self::html_th,Self::html_td))
             .expect("write to string can't fail");
         html
     }
@@ -1,4 +1,4 @@
-use crate::{Game, BoardCell, Player, UnitType, MAX_HEALTH};
+use crate::{Game, Player, UnitType, CoordPair, UnitTotals, MAX_HEALTH};
 
 use std::{ops::{Deref, Add, Mul, Sub, Neg}, sync::Arc};
 use rand::Rng;
@@ -8,6 +8,9 @@ pub type HeuristicScore = i32;
 pub const MIN_HEURISTIC_SCORE : HeuristicScore = HeuristicScore::MIN;
 pub const MAX_HEURISTIC_SCORE : HeuristicScore = HeuristicScore::MAX;
 
+// game phase goes from 0 (opening) to PHASE_MAX (endgame)
+pub const PHASE_MAX : HeuristicScore = 256;
+
 #[derive(Clone)]
 pub struct Heuristic {
     function : Arc<dyn HeuristicFn>,
@@ -115,6 +118,23 @@ pub struct Heuristics {
 }
 
 impl Heuristics {
+    // raiders and invaders play the attacker's heuristics
+    pub fn get(&self, player: Player, maximizing_player: bool) -> &Heuristic {
+        match (!player.is_defender(), maximizing_player) {
+            (true, true) => &self.attacker_max,
+            (true, false) => &self.attacker_min,
+            (false, true) => &self.defender_max,
+            (false, false) => &self.defender_min,
+        }
+    }
+    pub fn get_mut(&mut self, player: Player, maximizing_player: bool) -> &mut Heuristic {
+        match (!player.is_defender(), maximizing_player) {
+            (true, true) => &mut self.attacker_max,
+            (true, false) => &mut self.attacker_min,
+            (false, true) => &mut self.defender_max,
+            (false, false) => &mut self.defender_min,
+        }
+    }
     pub fn set_attack_heuristics(&mut self, h: Heuristic) {
         self.attacker_max = h.clone();
         self.defender_min = h;
@@ -148,6 +168,59 @@ impl Default for Heuristics {
     }
 }
 
+pub struct HeuristicPreset {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub heuristic: fn() -> Heuristic,
+}
+
+pub const HEURISTIC_PRESETS : &[HeuristicPreset] = &[
+    HeuristicPreset { 
+        name: "score", 
+        description: "unit scores without health (e1)",
+        heuristic: score_heuristic,
+    },
+    HeuristicPreset { 
+        name: "default", 
+        description: "unit scores with health, prefers shorter games (e2)",
+        heuristic: <Heuristic as Default>::default,
+    },
+    HeuristicPreset { 
+        name: "attacker", 
+        description: "default attacker heuristic: health, local combat and distance to AI (e3)",
+        heuristic: default_attacker_heuristic,
+    },
+    HeuristicPreset { 
+        name: "attacker-tapered", 
+        description: "attacker heuristic that goes from cautious to aggressive as the game advances",
+        heuristic: tapered_attacker_heuristic,
+    },
+    HeuristicPreset { 
+        name: "defender", 
+        description: "default defender heuristic: unit scores with health (e4)",
+        heuristic: default_defender_heuristic,
+    },
+    HeuristicPreset { 
+        name: "ai-distance", 
+        description: "only the distance of offensive units to the AI",
+        heuristic: || ai_distance(5, 1),
+    },
+    HeuristicPreset { 
+        name: "local-combat", 
+        description: "only the outcome of local combat between adjacent units",
+        heuristic: local_combat,
+    },
+    HeuristicPreset { 
+        name: "random", 
+        description: "random values (weak baseline)",
+        heuristic: || random_value(-1000, 1000),
+    },
+];
+
+pub fn heuristic_preset(name: &str) -> Option<&'static HeuristicPreset> {
+    HEURISTIC_PRESETS.iter().find(|preset| preset.name == name)
+}
+
 pub fn score_heuristic() -> Heuristic {
     // simple score total by unit without health
     units_score_health_weights_bias(1,1,1,0,unit_score)
@@ -160,27 +233,52 @@ pub fn default_attacker_heuristic() -> Heuristic {
         - game_moves() * 10
 }
 
+// keeps the units healthy while they get closer to the enemy AI
+pub fn opening_attacker_heuristic() -> Heuristic {
+    units_score_health_weights_bias(1,1,50, 1, unit_score) * 10
+        + local_combat() * 2
+        + ai_distance(2, 1)
+        - game_moves() * 10
+}
+
+// the defender wins when max_moves is reached so the attacker must take more risks near the end
+pub fn endgame_attacker_heuristic() -> Heuristic {
+    units_score_health_weights_bias(1,1,50, 1, unit_score) * 10
+        + local_combat() * 10
+        + ai_distance(20, 1)
+        - game_moves() * 10
+}
+
+pub fn tapered_attacker_heuristic() -> Heuristic {
+    tapered(opening_attacker_heuristic(), default_attacker_heuristic(), endgame_attacker_heuristic())
+}
+
 pub fn default_defender_heuristic() -> Heuristic {
     units_score_health_weights_bias(1,1,10, 1, unit_score)
 }
 
 pub fn ai_distance(weight_friend: HeuristicScore, weight_opponent: HeuristicScore) -> Heuristic {
     Heuristic::new(move|game: &Game, player : Player| {
-        game.unit_coord_pairs().map(|(pair,from_cell,to_cell)| {
-            let from_player = from_cell.player().expect("not empty");
-            let from_unit_type = from_cell.unit().expect("not empty").unit_type;
-            let to_player = to_cell.player().expect("not empty");
-            let to_unit_type = to_cell.unit().expect("not empty").unit_type;
-            let dist = pair.moves_distance() as HeuristicScore;
-            if from_player == player && to_player != player && 
-                from_unit_type != UnitType::AI && from_unit_type != UnitType::Tech && to_unit_type == UnitType::AI {
-                weight_friend as f32 * from_unit_type.damage_amount(&to_unit_type) as f32 / dist as f32
-            } else if from_player != player && to_player == player && 
-                from_unit_type != UnitType::AI && from_unit_type != UnitType::Tech && to_unit_type == UnitType::AI {
-                weight_opponent as f32 * from_unit_type.damage_amount(&to_unit_type) as f32 / -dist as f32
-            } else {
-                0.0
+        // AI coordinates are tracked by the game so this is linear in the number of units
+        let totals = game.unit_totals();
+        game.unit_coords().map(|(from,from_cell)| {
+            let (&from_player, from_unit) = from_cell.player_unit().expect("not empty");
+            let from_unit_type = from_unit.unit_type;
+            if from_unit_type == UnitType::AI || from_unit_type == UnitType::Tech {
+                return 0.0;
             }
+            let damage = game.rules().damage_amount(from_unit_type, UnitType::AI) as f32;
+            Player::all().filter(|&to_player| !game.are_allies(to_player, from_player)).filter_map(|to_player| {
+                let to = totals.ai_coord(to_player)?;
+                let dist = CoordPair::new(from,to).moves_distance() as f32;
+                if game.are_allies(from_player, player) {
+                    Some(weight_friend as f32 * damage / dist)
+                } else if game.are_allies(to_player, player) {
+                    Some(weight_opponent as f32 * damage / -dist)
+                } else {
+                    None
+                }
+            }).sum::<f32>()
         }).sum::<f32>() as HeuristicScore
     })
 }
@@ -198,10 +296,11 @@ pub fn local_combat() -> Heuristic {
                         continue;
                     }
                     let (to_player, to_unit) = to_cell.player_unit().expect("to cell should not be empty");
-                    if from_player != to_player {
-                        let dmg_from = to_unit.unit_type.damage_amount(&from_unit.unit_type);
+                    let dmg_from = game.rules().damage_amount(to_unit.unit_type, from_unit.unit_type);
+                    // rule sets can contain zero damage entries
+                    if !game.are_allies(*from_player, *to_player) && dmg_from > 0 {
                         let health_from = from_unit.health;
-                        from_rounds_alive = std::cmp::min(from_rounds_alive,(health_from + dmg_from - 1) / dmg_from);
+                        from_rounds_alive = std::cmp::min(from_rounds_alive,health_from.div_ceil(dmg_from));
                     };
                 }
             }
@@ -211,17 +310,17 @@ pub fn local_combat() -> Heuristic {
                         continue;
                     }
                     let (to_player, to_unit) = to_cell.player_unit().expect("to cell should not be empty");
-                    if from_player != to_player {
-                        let dmg_to = from_unit.unit_type.damage_amount(&to_unit.unit_type);
+                    let dmg_to = game.rules().damage_amount(from_unit.unit_type, to_unit.unit_type);
+                    if !game.are_allies(*from_player, *to_player) && dmg_to > 0 {
                         let health_to = to_unit.health;
-                        let to_rounds_alive = (health_to + dmg_to - 1) / dmg_to;
+                        let to_rounds_alive = health_to.div_ceil(dmg_to);
                         if from_rounds_alive > to_rounds_alive {
                             best_score = std::cmp::max(best_score,unit_score(from_unit.unit_type));
                         }
                     };
                 }
             }
-            if &player == from_player {
+            if game.are_allies(player, *from_player) {
                 total_score += best_score;   
             } else {
                 total_score -= best_score;   
@@ -231,6 +330,45 @@ pub fn local_combat() -> Heuristic {
     })
 }
 
+pub fn game_phase_value(game: &Game) -> HeuristicScore {
+    let material = |totals: &UnitTotals| Player::all()
+        .map(|player| totals.material(player, unit_score))
+        .sum::<HeuristicScore>();
+    let initial_material = material(game.initial_unit_totals());
+    let material_phase = if initial_material > 0 {
+        let lost = (initial_material - material(game.unit_totals())).max(0);
+        PHASE_MAX * lost / initial_material
+    } else {
+        0
+    };
+    let moves_phase = match game.options().max_moves {
+        Some(max_moves) if max_moves > 0 => {
+            let moves = std::cmp::min(game.total_moves(), max_moves);
+            (PHASE_MAX as usize * moves / max_moves) as HeuristicScore
+        },
+        _ => 0,
+    };
+    std::cmp::max(material_phase, moves_phase)
+}
+
+pub fn game_phase() -> Heuristic {
+    Heuristic::new(|game: &Game,_| game_phase_value(game))
+}
+
+pub fn tapered(opening: Heuristic, middlegame: Heuristic, endgame: Heuristic) -> Heuristic {
+    // linear interpolation between opening and middlegame, then between middlegame and endgame
+    Heuristic::new(move|game: &Game, player: Player| {
+        let phase = game_phase_value(game) as i64;
+        let half = (PHASE_MAX / 2) as i64;
+        let (from, to, weight) = if phase <= half {
+            (opening(game,player), middlegame(game,player), phase)
+        } else {
+            (middlegame(game,player), endgame(game,player), phase - half)
+        };
+        ((from as i64 * (half - weight) + to as i64 * weight) / half) as HeuristicScore
+    })
+}
+
 pub fn constant_value(value: HeuristicScore) -> Heuristic {
     Heuristic::new(move|_,_| value)
 }
@@ -248,29 +386,20 @@ pub fn units_score_health_weights_bias(weight_friend: HeuristicScore,
     score_fn: fn(UnitType) -> HeuristicScore) -> Heuristic 
 {
     Heuristic::new(
-        move|game:&Game,player:Player| 
-            game.units().map(|cell|
-                units_score_health_cell(cell,&player,weight_friend,weight_opponent,bias_health, weight_health, score_fn))
-            .sum()
-    )
-}
-
-fn units_score_health_cell(cell: &BoardCell, current_player: &Player, 
-    weight_friend: HeuristicScore, weight_opponent: HeuristicScore, 
-    bias_health: HeuristicScore, weight_health: HeuristicScore,
-    score_fn: fn(UnitType) -> HeuristicScore) -> HeuristicScore 
-{
-    if cell.is_empty() {
-        0
-    } else {
-        let (player, unit) = cell.player_unit().expect("must call with a cell containing a unit");
-        let score = score_fn(unit.unit_type)*(bias_health+weight_health*unit.health as HeuristicScore);
-        if player == current_player {
-            weight_friend * score
-        } else {
-            weight_opponent * -score
+        move|game:&Game,current_player:Player| {
+            // computed from the running totals instead of scanning the board
+            let totals = game.unit_totals();
+            Player::all().map(|player| {
+                let score = bias_health * totals.material(player, score_fn) 
+                    + weight_health * totals.weighted_health(player, score_fn);
+                if game.are_allies(player, current_player) {
+                    weight_friend * score
+                } else {
+                    weight_opponent * -score
+                }
+            }).sum()
         }
-    }
+    )
 }
 
 pub fn unit_score(unit_type: UnitType) -> HeuristicScore {
@@ -281,6 +410,7 @@ pub fn unit_score(unit_type: UnitType) -> HeuristicScore {
         Tech => 3,
         Firewall => 1,
         Program => 1,
+        Mortar => 2,
     }
 }
 
@@ -0,0 +1,150 @@
+use std::io::{BufRead, BufReader, Write, Result as IoResult};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::time::Duration;
+
+// just enough HTTP/1.1 for the local servers: one request per connection, bodies sized by Content-Length
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    // without the query string
+    pub path: String,
+    pub query: Vec<(String,String)>,
+    pub body: String,
+}
+
+// requests bigger than this are cut short (moves and game states are much smaller)
+const MAX_BODY_LEN : usize = 1 << 20;
+
+// clients that send nothing for this long are dropped (so that they don't keep a thread busy)
+const READ_TIMEOUT : Duration = Duration::from_secs(10);
+
+fn invalid_data(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+// %XX escapes and '+' for spaces
+fn url_decode(s: &str) -> String {
+    let mut bytes = Vec::new();
+    let mut rest = s.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' if rest.len() >= 2 => match u8::from_str_radix(&String::from_utf8_lossy(&rest[..2]), 16) {
+                Ok(decoded) => {
+                    bytes.push(decoded);
+                    rest = &rest[2..];
+                },
+                Err(_) => bytes.push(byte),
+            },
+            _ => bytes.push(byte),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+impl Request {
+    pub fn read(r: &mut impl BufRead) -> IoResult<Self> {
+        let mut line = String::new();
+        r.read_line(&mut line)?;
+        let mut words = line.split_whitespace();
+        let (method, target) = words.next().zip(words.next()).ok_or(invalid_data("invalid request line"))?;
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let query = query.split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+            .map(|(key, value)| (url_decode(key), url_decode(value)))
+            .collect();
+        let mut content_length = 0;
+        loop {
+            let mut header = String::new();
+            if r.read_line(&mut header)? == 0 || header.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse::<usize>().map_err(|_| invalid_data("invalid content length"))?;
+                }
+            }
+        }
+        let mut body = vec![0; content_length.min(MAX_BODY_LEN)];
+        r.read_exact(&mut body)?;
+        Ok(Self {
+            method: method.to_ascii_uppercase(),
+            path: url_decode(path),
+            query,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        })
+    }
+    pub fn query(&self, key: &str) -> Option<&str> {
+        self.query.iter().find(|(k, _)| k == key).map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    pub fn new(status: u16, content_type: &'static str, body: impl Into<String>) -> Self {
+        Self { status, content_type, body: body.into() }
+    }
+    pub fn html(body: impl Into<String>) -> Self {
+        Self::new(200, "text/html; charset=utf-8", body)
+    }
+    pub fn json(status: u16, value: &serde_json::Value) -> Self {
+        Self::new(status, "application/json", value.to_string())
+    }
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            409 => "Conflict",
+            _ => "Unknown",
+        }
+    }
+    pub fn write_to(&self, w: &mut impl Write) -> IoResult<()> {
+        write!(w, "HTTP/1.1 {} {}\r\n", self.status, self.reason())?;
+        write!(w, "Content-Type: {}\r\n", self.content_type)?;
+        write!(w, "Content-Length: {}\r\n", self.body.len())?;
+        write!(w, "Cache-Control: no-store\r\n")?;
+        write!(w, "Connection: close\r\n\r\n")?;
+        w.write_all(self.body.as_bytes())?;
+        w.flush()
+    }
+}
+
+fn handle_connection(stream: TcpStream, handler: &(impl Fn(&Request) -> Response + ?Sized)) -> IoResult<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let response = match Request::read(&mut reader) {
+        Ok(request) => handler(&request),
+        Err(error) => Response::new(400, "text/plain", error.to_string()),
+    };
+    response.write_to(&mut &stream)
+}
+
+// answers the connections of the listener with the handler, one thread per connection (never returns:
+// connections that fail to be accepted are skipped)
+pub fn serve(listener: TcpListener, handler: impl Fn(&Request) -> Response + Send + Sync + 'static) -> IoResult<()> {
+    let handler = Arc::new(handler);
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            // out of file descriptors? give the other connections time to close
+            std::thread::sleep(Duration::from_millis(10));
+            continue;
+        };
+        let handler = handler.clone();
+        std::thread::spawn(move || {
+            // a client that went away is not an error of the server
+            let _ = handle_connection(stream, handler.as_ref());
+        });
+    }
+    Ok(())
+}
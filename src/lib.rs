@@ -8,17 +8,35 @@ pub mod player;
 pub mod actions;
 pub mod coord;
 pub mod heuristics;
+pub mod rules;
+pub mod scenario;
+pub mod terrain;
+pub mod effects;
+pub mod error;
+#[cfg(any(feature="broker", feature="server"))]
+pub mod protocol;
+#[cfg(feature="server")]
+pub mod http;
+#[cfg(feature="server")]
+pub mod server;
+#[cfg(feature="server")]
+pub mod broker_server;
 
 pub type Dim = i8;
 pub use coord::{Coord, CoordPair, CoordTuple};
 type Health = u8;
-pub use game::{Game,GameOptions};
+pub use game::{Game,GameOptions,GameResult,UnitTotals};
 pub use board::Board;
 pub use cell::{BoardCell,BoardCellData};
 pub use unit_type::UnitType;
 pub use player::Player;
-pub use actions::{Action, ActionOutcome, IsUsefulInfo};
+pub use actions::{Action, ActionOutcome, IsUsefulInfo, ParseActionError};
 pub use heuristics::{HeuristicScore,Heuristics};
+pub use rules::RuleSet;
+pub use scenario::Scenario;
+pub use terrain::Terrain;
+pub use effects::{Effects, CombatEffects, InflictedEffects};
+pub use error::{WargameError, BrokerError};
 
 const MAX_HEALTH : Health = 9;
 pub const DEFAULT_BOARD_DIM : Dim = 5;
@@ -54,11 +72,7 @@ pub fn number_digits_precision_to_string(float: f64, precision: usize) -> String
     let precision = if a >= 1. {
         // reduce by number of digits, minimum 0
         let n = (1. + a.log10().floor()) as usize;
-        if n <= precision {
-            precision - n
-        } else {
-            0
-        }
+        precision.saturating_sub(n)
     // if precision is less than 1 (but non-zero), then precision becomes greater than "standard"
     } else if a > 0. {
         // increase number of digits
@@ -1,6 +1,7 @@
 use std::process::exit;
+use std::io::Write;
 
-use ai_wargame::{Game, GameOptions, Player};
+use ai_wargame::{Game, GameOptions, WargameError, Player, Scenario, heuristics::{self, Heuristics}, scenario::{BUNDLED_SCENARIOS, parse_dims}, game::victory::parse_tie_break};
 
 fn print_usage(program: &str, opts: getopts::Options) {
     let my_name = option_env!("CARGO_PKG_NAME").unwrap_or(program);
@@ -17,6 +18,38 @@ enum PlayType {
     Manual,
 }
 
+fn print_heuristics() {
+    println!("Available heuristics:");
+    for preset in heuristics::HEURISTIC_PRESETS {
+        println!("  {:<14}{}", preset.name, preset.description);
+    }
+}
+
+fn print_scenarios() {
+    println!("Bundled scenarios:");
+    for (name, text) in BUNDLED_SCENARIOS {
+        // the first comment line of a scenario describes it
+        let description = text.lines().next().and_then(|l| l.strip_prefix('#')).unwrap_or_default().trim();
+        println!("  {:<14}{}", name, description);
+    }
+}
+
+// MAX[,MIN] sets the heuristics used by a player's search (MIN is left unchanged if omitted)
+fn set_player_heuristics(heuristics: &mut Heuristics, player: Player, names: &str) -> Result<(),String> {
+    let mut slots = names.split(',');
+    for maximizing_player in [true, false] {
+        if let Some(name) = slots.next() {
+            let preset = heuristics::heuristic_preset(name.trim())
+                .ok_or(format!("unknown heuristic: {name}"))?;
+            *heuristics.get_mut(player, maximizing_player) = (preset.heuristic)();
+        }
+    }
+    if slots.next().is_some() {
+        return Err(format!("too many heuristics for {player}: {names}"));
+    }
+    Ok(())
+}
+
 fn main() {
     let args: Vec<String> = std::env::args().collect();
     let program = args[0].clone();
@@ -27,9 +60,42 @@ fn main() {
     opts.optopt("s", "seconds", "maximum search time in seconds", "FLOAT");
     opts.optopt("m", "moves", "maximum moves in a game", "INT");
     opts.optopt("H", "heuristics", "select heuristics set to use", "e1|e2|e3e4");
+    opts.optopt("", "attacker-heuristic", "heuristics for the attacker's search (see --list-heuristics)", "MAX[,MIN]");
+    opts.optopt("", "defender-heuristic", "heuristics for the defender's search (see --list-heuristics)", "MAX[,MIN]");
+    opts.optflag("", "list-heuristics", "list the available heuristics");
+    opts.optopt("", "rules", "load unit stats and tables from a rules file", "FILE");
+    opts.optflag("", "print-rules", "print the rules in the rules file format and exit");
+    opts.optopt("", "scenario", "initial setup: a bundled scenario or a scenario file", "NAME|FILE");
+    opts.optflag("", "list-scenarios", "list the bundled scenarios");
+    opts.optopt("", "dim", "board dimensions for the classic setup (defaults to 5, or 8 with more than 2 players)", "N|ROWSxCOLS");
+    opts.optopt("", "players", "number of armies for the classic setup (defaults to 2, 3 and 4 add the raider and invader)", "2|3|4");
+    opts.optopt("", "teams", "allied players share victory and can't attack each other", "PLAYER+PLAYER[,...]");
+    opts.optopt("", "action-points", "several actions per turn: K per turn or K per unit (end the turn early with end)", "turn:K|unit:K");
+    opts.optopt("", "reinforcements", "AIs can spawn SPAWNS units next to them, one every COOLDOWN turns (enter spawn TYPE COORD)", "SPAWNS[:COOLDOWN]");
+    opts.optopt("", "fog", "fog of war: players only see units within this distance of their own", "INT");
+    opts.optopt("", "fog-samples", "number of determinized games searched by the computer under fog of war (defaults to 8)", "INT");
+    opts.optopt("", "repetition", "repeated positions end the game (threefold unless COUNT is given)", "off|draw|defender[:COUNT]");
+    opts.optopt("", "elimination", "a player loses with their AI (default) or all their units", "ai|all-units");
+    opts.optopt("", "move-cap", "result at the maximum number of moves: tie-break (default) or most health left", "tie-break|material");
+    opts.optopt("", "hill", "king of the hill: holding the zone at the start of your turn wins", "COORD|COORD-COORD");
+    opts.optopt("", "tie-break", "result when nothing else decides the game (defaults to defender)", "attacker|defender|draw");
+    opts.optopt("", "record", "write the actions of the game to a file, one per line in the explicit notation", "FILE");
+    opts.optopt("", "replay", "play the actions of a record file (explicit notation or coordinate pairs) before going on", "FILE");
+    opts.optopt("", "features-csv", "export position features of self-play games to a CSV file", "FILE");
+    opts.optopt("", "games", "number of self-play games for the CSV export (defaults to 1)", "INT");
 
     #[cfg(feature="broker")]
     opts.optopt("b", "broker", "specify url of game broker to use for moves", "URL");
+    #[cfg(feature="broker")]
+    opts.optopt("", "broker-timeout", "seconds before a request to the game broker fails (defaults to 5)", "SECONDS");
+    #[cfg(feature="broker")]
+    opts.optopt("", "broker-retries", "failed requests in a row before giving up on the game broker (defaults to 5)", "INT");
+    #[cfg(feature="server")]
+    opts.optopt("", "serve", "serve the game in a browser at this address (e.g. 127.0.0.1:8000)", "ADDR");
+    #[cfg(feature="server")]
+    opts.optopt("", "broker-serve", "run a game broker at this address for --broker clients (e.g. 127.0.0.1:8001)", "ADDR");
+    #[cfg(feature="tui")]
+    opts.optflag("", "tui", "play in a full-screen terminal interface (select cells with the arrow keys)");
 
     opts.optflag("R", "no-rand-traversal", "disable random traversal of possible actions");
     opts.optflag("A", "no-auto-depth", "don't try to auto adjust the search depth dynamically");
@@ -57,6 +123,27 @@ fn main() {
         print_usage(&program, opts);
         exit(0);
     }
+    if matches.opt_present("list-heuristics") {
+        print_heuristics();
+        exit(0);
+    }
+    if matches.opt_present("list-scenarios") {
+        print_scenarios();
+        exit(0);
+    }
+
+    #[cfg(feature="server")]
+    if let Some(addr) = matches.opt_str("broker-serve") {
+        let served = std::net::TcpListener::bind(&addr).and_then(|listener| {
+            println!("Game broker listening at http://{}/CHANNEL", listener.local_addr()?);
+            ai_wargame::broker_server::BrokerServer::new().serve(listener)
+        });
+        if let Err(error) = served {
+            eprintln!("Could not run the game broker at {addr}: {error}");
+            exit(1)
+        }
+        exit(0);
+    }
 
     let mut options = GameOptions::default();
 
@@ -82,6 +169,92 @@ fn main() {
         }
     };
 
+    if let Some(path) = matches.opt_str("rules") {
+        match ai_wargame::RuleSet::from_file(&path) {
+            Ok(rules) => options.rules = rules,
+            Err(error) => {
+                eprintln!("Could not load rules: {error}");
+                exit(1)
+            }
+        }
+    }
+    if matches.opt_present("print-rules") {
+        print!("{}", options.rules);
+        exit(0);
+    }
+
+    if let Some(players) = matches.opt_str("players") {
+        match players.parse::<usize>() {
+            Ok(count) if (2..=Player::cardinality()).contains(&count) => {
+                options.players = Player::all().take(count).collect();
+                // armies need room to deploy in every corner
+                if count > 2 {
+                    (options.rows, options.cols) = (8, 8);
+                }
+            },
+            _ => {
+                eprintln!("Invalid number of players (2 to {}): {players}", Player::cardinality());
+                exit(1)
+            }
+        }
+    }
+    if let Some(action_points) = matches.opt_str("action-points") {
+        match action_points.parse() {
+            Ok(action_points) => options.action_points = Some(action_points),
+            Err(error) => {
+                eprintln!("Invalid action points: {error}");
+                exit(1)
+            }
+        }
+    }
+    if let Some(reinforcements) = matches.opt_str("reinforcements") {
+        match reinforcements.parse() {
+            Ok(reinforcements) => options.reinforcements = Some(reinforcements),
+            Err(error) => {
+                eprintln!("Invalid reinforcements: {error}");
+                exit(1)
+            }
+        }
+    }
+    if let Some(teams) = matches.opt_str("teams") {
+        match teams.parse() {
+            Ok(teams) => options.teams = teams,
+            Err(error) => {
+                eprintln!("Invalid teams: {error}");
+                exit(1)
+            }
+        }
+    }
+
+    match (matches.opt_str("scenario"), matches.opt_str("dim")) {
+        (Some(_), Some(_)) => {
+            eprintln!("--dim only applies to the classic setup (the scenario sets the board dimensions)");
+            exit(1)
+        },
+        (Some(name), None) => {
+            let scenario = Scenario::load(&name)
+                .and_then(|scenario| scenario.validate(&options.rules).map(|_| scenario));
+            match scenario {
+                Ok(scenario) => options.scenario = Some(scenario),
+                Err(error) => {
+                    eprintln!("Could not load scenario: {error}");
+                    exit(1)
+                }
+            }
+        },
+        (None, Some(dim)) => {
+            let min_dim = if options.players.len() > 2 { 6 } else { 4 };
+            match parse_dims(&dim) {
+                Ok((rows, cols)) if rows >= min_dim && cols >= min_dim => (options.rows, options.cols) = (rows, cols),
+                _ => {
+                    eprintln!("Invalid board dimensions (minimum of {min_dim}x{min_dim}): {dim}");
+                    exit(1)
+                }
+            }
+        },
+        (None, None) => {},
+    }
+
     options.debug = !matches.opt_present("no-debug");
     options.rand_traversal = !matches.opt_present("no-rand-traversal");
     options.adjust_max_depth = !matches.opt_present("no-auto-depth");
@@ -111,6 +284,58 @@ fn main() {
             exit(1)
         },
     }
+    for (player, option) in [(Player::Attacker, "attacker-heuristic"), (Player::Defender, "defender-heuristic")] {
+        if let Some(names) = matches.opt_str(option) {
+            if let Err(error) = set_player_heuristics(&mut options.heuristics, player, &names) {
+                println!("{error}\n");
+                print_heuristics();
+                exit(1)
+            }
+        }
+    }
+
+    if let Some(radius) = matches.opt_str("fog") {
+        match radius.parse() {
+            Ok(radius) if radius >= 1 => options.fog_radius = Some(radius),
+            _ => {
+                eprintln!("Invalid fog of war radius (minimum of 1): {radius}");
+                exit(1)
+            }
+        }
+    }
+    if let Some(samples) = matches.opt_str("fog-samples").and_then(|s|s.parse::<usize>().ok()) {
+        options.fog_samples = samples;
+    }
+
+    if let Some(rule) = matches.opt_str("repetition") {
+        match rule.parse() {
+            Ok(rule) => options.repetition = rule,
+            Err(error) => {
+                eprintln!("{error}");
+                exit(1)
+            }
+        }
+    }
+
+    let victory = (|| -> Result<(),anyhow::Error> {
+        if let Some(elimination) = matches.opt_str("elimination") {
+            options.victory.elimination = elimination.parse()?;
+        }
+        if let Some(move_cap) = matches.opt_str("move-cap") {
+            options.victory.move_cap = move_cap.parse()?;
+        }
+        if let Some(hill) = matches.opt_str("hill") {
+            options.victory.hill = Some(hill.parse()?);
+        }
+        if let Some(tie_break) = matches.opt_str("tie-break") {
+            options.victory.tie_break = parse_tie_break(&tie_break)?;
+        }
+        Ok(())
+    })();
+    if let Err(error) = victory {
+        eprintln!("Invalid victory conditions: {error}");
+        exit(1)
+    }
 
     options.multi_threaded = false;
     #[cfg(feature="rayon")]
@@ -127,6 +352,25 @@ fn main() {
     #[cfg(feature="broker")]
     {
         options.broker = matches.opt_str("broker");
+        if let Some(timeout) = matches.opt_str("broker-timeout").and_then(|s|s.parse::<f32>().ok()) {
+            options.broker_timeout = timeout;
+        }
+        if let Some(retries) = matches.opt_str("broker-retries").and_then(|s|s.parse::<usize>().ok()) {
+            options.broker_max_failures = retries.max(1);
+        }
+    }
+
+    if let Some(path) = matches.opt_str("features-csv") {
+        let games = matches.opt_str("games").and_then(|s|s.parse::<usize>().ok()).unwrap_or(1);
+        let result = std::fs::File::create(&path).and_then(|file| {
+            let mut w = std::io::BufWriter::new(file);
+            Game::self_play_features_csv(options, games, &mut w)
+        });
+        if let Err(error) = result {
+            eprintln!("Could not export features to {path}: {error}");
+            exit(1);
+        }
+        exit(0);
     }
 
     let mut game = Game::new(options);
@@ -140,26 +384,140 @@ fn main() {
         }
     }
 
+    if let Some(path) = matches.opt_str("replay") {
+        let replayed = std::fs::read_to_string(&path)
+            .map_err(WargameError::from)
+            .and_then(|record| game.replay(&record));
+        match replayed {
+            Ok(played) => {
+                for (player, action, _) in played {
+                    println!("{player}: {action}");
+                }
+            },
+            Err(error) => {
+                eprintln!("Could not replay {path}: {error}");
+                exit(1)
+            }
+        }
+    }
+
+    let mut record = match matches.opt_str("record").map(std::fs::File::create).transpose() {
+        Ok(record) => record.map(std::io::LineWriter::new),
+        Err(error) => {
+            eprintln!("Could not create the record file: {error}");
+            exit(1)
+        }
+    };
+
+    // players the computer doesn't play
+    #[cfg(any(feature="server", feature="tui"))]
+    let humans = match play_type {
+        PlayType::Attack => vec![Player::Attacker],
+        PlayType::Defend => vec![Player::Defender],
+        PlayType::Manual => game.players().to_vec(),
+        PlayType::Auto => Vec::new(),
+    };
+
+    #[cfg(feature="server")]
+    if let Some(addr) = matches.opt_str("serve") {
+        let served = std::net::TcpListener::bind(&addr).and_then(|listener| {
+            println!("Serving the game at http://{}/", listener.local_addr()?);
+            ai_wargame::server::GameServer::new(game, humans).serve(listener)
+        });
+        if let Err(error) = served {
+            eprintln!("Could not serve the game at {addr}: {error}");
+            exit(1)
+        }
+        exit(0);
+    }
+
+    #[cfg(feature="tui")]
+    if matches.opt_present("tui") {
+        let played = game.tui_play(&humans, |action| match record.as_mut() {
+            Some(record) => writeln!(record, "{action}"),
+            None => Ok(()),
+        });
+        if let Err(error) = played {
+            eprintln!("Terminal interface error: {error}");
+            exit(1)
+        }
+        if let Some(message) = game.end_game_message() {
+            println!("{message}");
+        }
+        exit(0);
+    }
+
     loop {
+        // moves the broker has past this game (another client went on or a reply was lost)
+        #[cfg(feature="broker")]
+        if game.options().broker.is_some() && game.end_game_result().is_none() {
+            match game.broker_resync() {
+                Ok(played) if !played.is_empty() => {
+                    for (player, action, _) in played {
+                        println!("{player} (from the game broker): {action}");
+                        if let Some(record) = record.as_mut() {
+                            if let Err(error) = writeln!(record, "{action}") {
+                                eprintln!("Could not write to the record file: {error}");
+                                exit(1)
+                            }
+                        }
+                    }
+                    continue;
+                },
+                Ok(_) => (),
+                Err(error) => {
+                    eprintln!("Could not catch up with the game broker: {error}");
+                    exit(1)
+                }
+            }
+        }
+
+        // under fog of war the board is shown as the human player sees it (fully once the game ends)
+        let viewer = match play_type {
+            _ if game.end_game_result().is_some() => None,
+            PlayType::Attack => Some(Player::Attacker),
+            PlayType::Defend => Some(Player::Defender),
+            PlayType::Manual => Some(game.player()),
+            PlayType::Auto => None,
+        };
         println!();
-        game.console_pretty_print();
+        game.console_pretty_print_for(viewer);
         println!();
 
-        if let Some(winner) = game.end_game_result() {
-            println!("{} wins in {} moves!", winner, game.total_moves());
+        if let Some(message) = game.end_game_message() {
+            println!("{message}");
+            #[cfg(feature="broker")]
+            if game.options().broker.is_some() {
+                if let Err(error) = game.broker_post_result() {
+                    eprintln!("Could not report the result to the game broker: {error}");
+                }
+            }
             break;
         }
 
-        match (&play_type, game.player()) {
+        let played = match (&play_type, game.player()) {
             (PlayType::Defend, Player::Defender) | 
             (PlayType::Attack, Player::Attacker) |
             (PlayType::Manual, _) 
             => {
-                game.console_human_play_turn();
+                game.console_human_play_turn()
             },
             _ => {
-                game.console_computer_play_turn();
+                game.console_computer_play_turn()
             },
+        };
+        let played = match played {
+            Ok(played) => played,
+            Err(error) => {
+                eprintln!("Game broker error: {error}");
+                exit(1)
+            }
+        };
+        if let (Some(record), Some(action)) = (record.as_mut(), played) {
+            if let Err(error) = writeln!(record, "{action}") {
+                eprintln!("Could not write to the record file: {error}");
+                exit(1)
+            }
         }
     }
 }
\ No newline at end of file
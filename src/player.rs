@@ -1,12 +1,15 @@
-use crate::DisplayFirstLetter;
+use crate::{Dim, DisplayFirstLetter};
 use enum_iterator::Sequence;
 
-#[derive(Debug, PartialEq, PartialOrd, Default, Clone, Copy, Sequence)]
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Default, Clone, Copy, Sequence)]
 #[derive(derive_more::Display)]
 pub enum Player {
     #[default]
     Attacker,
     Defender,
+    // extra armies for 3 and 4 player games
+    Raider,
+    Invader,
 }
 
 impl Player {
@@ -29,6 +32,18 @@ impl Player {
         match self {
             Self::Attacker => 0,
             Self::Defender => 1,
+            Self::Raider => 2,
+            Self::Invader => 3,
+        }
+    }
+    // each army starts in its own corner and moves forward towards the opposite one:
+    // attacker bottom right, defender top left, raider top right, invader bottom left
+    pub const fn forward(&self) -> (Dim,Dim) {
+        match self {
+            Self::Attacker => (-1,-1),
+            Self::Defender => (1,1),
+            Self::Raider => (1,-1),
+            Self::Invader => (-1,1),
         }
     }
 }
@@ -39,6 +54,8 @@ impl TryFrom<u8> for Player {
         match value {
             0 => Ok(Self::Attacker),
             1 => Ok(Self::Defender),
+            2 => Ok(Self::Raider),
+            3 => Ok(Self::Invader),
             _ => Err(String::from("invalid index for player")),
         }
     }
@@ -0,0 +1,116 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{Coord, UnitType, Action, GameResult, Player};
+
+// version of the broker data sent by this client: version 1 is {from,to,turn} only,
+// with the action depending on the cells (from == to for a self-destruct)
+pub const BROKER_VERSION: u32 = 2;
+
+fn broker_version_1() -> u32 {
+    1
+}
+
+// version 1 data is sent back as it came
+fn is_broker_version_1(version: &u32) -> bool {
+    *version == 1
+}
+
+// kind of an action sent through the game broker (see Action::kind)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum BrokerAction {
+    Pass,
+    Move,
+    Repair,
+    Attack,
+    SelfDestruct,
+    Spawn,
+    End,
+}
+
+// result of a game reported to the game broker
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BrokerResult {
+    Attacker,
+    Defender,
+    Raider,
+    Invader,
+    Draw,
+}
+
+impl From<GameResult> for BrokerResult {
+    fn from(result: GameResult) -> Self {
+        match result {
+            GameResult::Winner(Player::Attacker) => Self::Attacker,
+            GameResult::Winner(Player::Defender) => Self::Defender,
+            GameResult::Winner(Player::Raider) => Self::Raider,
+            GameResult::Winner(Player::Invader) => Self::Invader,
+            GameResult::Draw => Self::Draw,
+        }
+    }
+}
+
+// a move sent through the game broker: turn is the number of actions played once it is played
+// (the number of moves without action points)
+// (the coordinates of spawns are in to, and pass or end have none)
+// a result without an action reports the end of the game after the move of that turn
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct BrokerData {
+    #[serde(default = "broker_version_1", skip_serializing_if = "is_broker_version_1")]
+    pub version: u32,
+    #[serde(rename = "action", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<BrokerAction>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<Coord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<Coord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit_type: Option<UnitType>,
+    pub turn: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<BrokerResult>,
+}
+
+impl BrokerData {
+    // self-destructs keep from == to so that version 1 clients read them as well
+    pub fn new(action: Action, turn: usize) -> Self {
+        let (kind, from, to, unit_type) = match action {
+            Action::Pass => (BrokerAction::Pass, None, None, None),
+            Action::Move { from, to } => (BrokerAction::Move, Some(from), Some(to), None),
+            Action::Repair { from, to } => (BrokerAction::Repair, Some(from), Some(to), None),
+            Action::Attack { from, to } => (BrokerAction::Attack, Some(from), Some(to), None),
+            Action::SelfDestruct { from } => (BrokerAction::SelfDestruct, Some(from), Some(from), None),
+            Action::Spawn { at, unit_type } => (BrokerAction::Spawn, None, Some(at), Some(unit_type)),
+            Action::EndTurn => (BrokerAction::End, None, None, None),
+        };
+        Self { version: BROKER_VERSION, kind: Some(kind), from, to, unit_type, turn, result: None }
+    }
+    pub fn new_result(result: GameResult, turn: usize) -> Self {
+        Self { version: BROKER_VERSION, kind: None, from: None, to: None, unit_type: None, turn, result: Some(result.into()) }
+    }
+    pub fn is_result(&self) -> bool {
+        self.kind.is_none() && self.from.is_none() && self.to.is_none() && self.result.is_some()
+    }
+    // the action as sent (None for version 1 moves, which depend on the cells, and incomplete data)
+    pub fn action(&self) -> Option<Action> {
+        match (self.kind?, self.from, self.to) {
+            (BrokerAction::Pass, _, _) => Some(Action::Pass),
+            (BrokerAction::End, _, _) => Some(Action::EndTurn),
+            (BrokerAction::Move, Some(from), Some(to)) => Some(Action::Move { from, to }),
+            (BrokerAction::Repair, Some(from), Some(to)) => Some(Action::Repair { from, to }),
+            (BrokerAction::Attack, Some(from), Some(to)) => Some(Action::Attack { from, to }),
+            (BrokerAction::SelfDestruct, Some(from), _) => Some(Action::SelfDestruct { from }),
+            (BrokerAction::Spawn, _, Some(at)) => Some(Action::Spawn { at, unit_type: self.unit_type? }),
+            _ => None,
+        }
+    }
+}
+
+// every reply of the broker (errors come with a 404 status)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrokerReply {
+    pub success: bool,
+    pub error: Option<String>,
+    pub data: Option<BrokerData>,
+}
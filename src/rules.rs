@@ -0,0 +1,323 @@
+use crate::{UnitType, Terrain, Dim, Health, MAX_HEALTH};
+
+use anyhow::anyhow;
+
+// farthest a unit can reach on the largest board
+pub const MAX_RANGE : Dim = Dim::MAX - 1;
+
+const UNIT_TYPES : usize = UnitType::cardinality();
+const TERRAINS : usize = enum_iterator::cardinality::<Terrain>();
+
+type Table = [[Health;UNIT_TYPES];UNIT_TYPES];
+
+// cells between a unit and the target of an attack at a distance that block it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(derive_more::Display)]
+pub enum LineOfSight {
+    None,
+    Walls,
+    // walls and units
+    #[default]
+    Units,
+}
+
+impl std::str::FromStr for LineOfSight {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "walls" => Ok(Self::Walls),
+            "units" => Ok(Self::Units),
+            _ => Err(anyhow!("expected none, walls or units: {s}")),
+        }
+    }
+}
+
+// unit stats and interaction tables (defaults to the built-in values of UnitType)
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleSet {
+    damage: Table,
+    repair: Table,
+    self_destruct: Table,
+    initial_health: [Health;UNIT_TYPES],
+    can_move_back: [bool;UNIT_TYPES],
+    can_move_while_engaged: [bool;UNIT_TYPES],
+    range: [Dim;UNIT_TYPES],
+    diagonal: [bool;UNIT_TYPES],
+    line_of_sight: [LineOfSight;UNIT_TYPES],
+    // status effects, all off with the built-in rules (see Effects)
+    infection: [u8;UNIT_TYPES],
+    infection_damage: [Health;UNIT_TYPES],
+    stun: [u8;UNIT_TYPES],
+    shield: [u8;UNIT_TYPES],
+    damage_reduction: [Health;TERRAINS],
+    terrain_repair: [Health;TERRAINS],
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        let table = |stat_fn: fn(&UnitType,&UnitType) -> Health| {
+            let mut table = Table::default();
+            for source in UnitType::all() {
+                for target in UnitType::all() {
+                    table[source.index()][target.index()] = stat_fn(&source, &target);
+                }
+            }
+            table
+        };
+        let mut rules = Self {
+            damage: table(UnitType::damage_amount),
+            repair: table(UnitType::repair_amount),
+            self_destruct: table(UnitType::self_destruct_amount),
+            initial_health: Default::default(),
+            can_move_back: Default::default(),
+            can_move_while_engaged: Default::default(),
+            range: Default::default(),
+            diagonal: Default::default(),
+            line_of_sight: Default::default(),
+            infection: Default::default(),
+            infection_damage: Default::default(),
+            stun: Default::default(),
+            shield: Default::default(),
+            damage_reduction: Default::default(),
+            terrain_repair: Default::default(),
+        };
+        rules.damage_reduction[Terrain::Fortified as usize] = 1;
+        rules.terrain_repair[Terrain::Depot as usize] = 1;
+        for unit_type in UnitType::all() {
+            rules.initial_health[unit_type.index()] = unit_type.initial_health();
+            rules.can_move_back[unit_type.index()] = unit_type.can_move_back();
+            rules.can_move_while_engaged[unit_type.index()] = unit_type.can_move_while_engaged();
+            rules.range[unit_type.index()] = unit_type.range();
+            rules.diagonal[unit_type.index()] = unit_type.can_attack_diagonally();
+            rules.line_of_sight[unit_type.index()] = unit_type.line_of_sight();
+        }
+        rules
+    }
+}
+
+impl RuleSet {
+    pub fn damage_amount(&self, source: UnitType, target: UnitType) -> Health {
+        self.damage[source.index()][target.index()]
+    }
+    pub fn repair_amount(&self, source: UnitType, target: UnitType) -> Health {
+        self.repair[source.index()][target.index()]
+    }
+    pub fn self_destruct_amount(&self, source: UnitType, target: UnitType) -> Health {
+        self.self_destruct[source.index()][target.index()]
+    }
+    pub fn initial_health(&self, unit_type: UnitType) -> Health {
+        self.initial_health[unit_type.index()]
+    }
+    pub fn can_move_back(&self, unit_type: UnitType) -> bool {
+        self.can_move_back[unit_type.index()]
+    }
+    pub fn can_move_while_engaged(&self, unit_type: UnitType) -> bool {
+        self.can_move_while_engaged[unit_type.index()]
+    }
+    pub fn range(&self, unit_type: UnitType) -> Dim {
+        self.range[unit_type.index()]
+    }
+    pub fn can_attack_diagonally(&self, unit_type: UnitType) -> bool {
+        self.diagonal[unit_type.index()]
+    }
+    pub fn line_of_sight(&self, unit_type: UnitType) -> LineOfSight {
+        self.line_of_sight[unit_type.index()]
+    }
+    // e.g. "3+diag" for a unit attacking up to 3 cells away along straight lines and diagonals
+    pub fn range_string(&self, unit_type: UnitType) -> String {
+        if self.can_attack_diagonally(unit_type) {
+            format!("{}+diag", self.range(unit_type))
+        } else {
+            self.range(unit_type).to_string()
+        }
+    }
+    // turns of infection given by a hit of the unit type
+    pub fn infection(&self, unit_type: UnitType) -> u8 {
+        self.infection[unit_type.index()]
+    }
+    // damage per turn of an infection given by the unit type
+    pub fn infection_damage(&self, unit_type: UnitType) -> Health {
+        self.infection_damage[unit_type.index()]
+    }
+    // turns a unit hit by the unit type can't move
+    pub fn stun(&self, unit_type: UnitType) -> u8 {
+        self.stun[unit_type.index()]
+    }
+    // units of the type have a shield absorbing a hit, back after this many turns (0 for no shield)
+    pub fn shield(&self, unit_type: UnitType) -> u8 {
+        self.shield[unit_type.index()]
+    }
+    pub fn has_effects(&self) -> bool {
+        [&self.infection, &self.stun, &self.shield].into_iter().flatten().any(|&turns| turns > 0)
+    }
+    // damage taken by a unit standing on the terrain is reduced by this amount
+    pub fn damage_reduction(&self, terrain: Terrain) -> Health {
+        self.damage_reduction[terrain as usize]
+    }
+    // health regained each turn by a unit standing on the terrain
+    pub fn terrain_repair(&self, terrain: Terrain) -> Health {
+        self.terrain_repair[terrain as usize]
+    }
+    pub fn self_destruct_string(&self) -> String {
+        let amounts = self.self_destruct.iter().flatten();
+        let (min, max) = (amounts.clone().min().unwrap(), amounts.max().unwrap());
+        if min == max {
+            min.to_string()
+        } else {
+            format!("{min} to {max} (depending on units)")
+        }
+    }
+    pub fn from_file(path: &str) -> Result<Self,anyhow::Error> {
+        let text = std::fs::read_to_string(path).map_err(|e| anyhow!("{path}: {e}"))?;
+        text.parse()
+    }
+    fn table_mut(&mut self, section: &str) -> Option<&mut Table> {
+        match section {
+            "damage" => Some(&mut self.damage),
+            "repair" => Some(&mut self.repair),
+            "self_destruct" => Some(&mut self.self_destruct),
+            _ => None,
+        }
+    }
+    fn write_table(f: &mut std::fmt::Formatter<'_>, section: &str, table: &Table) -> std::fmt::Result {
+        writeln!(f, "[{section}]")?;
+        write!(f, "{:<10}", "from/to")?;
+        for target in UnitType::all() {
+            write!(f, "{:>10}", target.to_string())?;
+        }
+        writeln!(f)?;
+        for source in UnitType::all() {
+            write!(f, "{:<10}", source.to_string())?;
+            for target in UnitType::all() {
+                write!(f, "{:>10}", table[source.index()][target.index()])?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_unit_type(name: &str) -> Result<UnitType,anyhow::Error> {
+    UnitType::all()
+        .find(|t| t.to_string().eq_ignore_ascii_case(name))
+        .ok_or(anyhow!("unknown unit type: {name}"))
+}
+
+fn parse_bool(value: &str) -> Result<bool,anyhow::Error> {
+    match value.to_ascii_lowercase().as_str() {
+        "yes" | "true" | "1" => Ok(true),
+        "no" | "false" | "0" => Ok(false),
+        _ => Err(anyhow!("expected yes or no: {value}")),
+    }
+}
+
+// text format (see Display): sections of tables with a header line naming the columns,
+// anything not specified keeps its built-in value
+impl std::str::FromStr for RuleSet {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rules = Self::default();
+        let mut section = String::new();
+        let mut header : Option<Vec<String>> = None;
+        for (line_index, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = |e: anyhow::Error| anyhow!("line {}: {e}", line_index+1);
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = name.trim().to_ascii_lowercase();
+                header = None;
+                continue;
+            }
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let Some(columns) = &header else {
+                header = Some(fields.iter().skip(1).map(|f| f.to_ascii_lowercase()).collect());
+                continue;
+            };
+            if fields.len() != columns.len() + 1 {
+                return Err(error(anyhow!("expected {} values", columns.len())));
+            }
+            if section == "terrain" {
+                let index = fields[0].parse::<Terrain>().map_err(error)? as usize;
+                for (column, value) in columns.iter().zip(&fields[1..]) {
+                    let amount = value.parse().map_err(|_| error(anyhow!("invalid amount: {value}")))?;
+                    match column.as_str() {
+                        "damage_reduction" => rules.damage_reduction[index] = amount,
+                        "repair" => rules.terrain_repair[index] = amount,
+                        _ => return Err(error(anyhow!("unknown terrain stat: {column}"))),
+                    }
+                }
+                continue;
+            }
+            let row_type = parse_unit_type(fields[0]).map_err(error)?;
+            for (column, value) in columns.iter().zip(&fields[1..]) {
+                if section == "units" {
+                    let index = row_type.index();
+                    match column.as_str() {
+                        "health" => rules.initial_health[index] = value.parse().ok().filter(|h: &Health| (1..=MAX_HEALTH).contains(h))
+                            .ok_or(error(anyhow!("invalid health (1 to {MAX_HEALTH}): {value}")))?,
+                        "move_back" => rules.can_move_back[index] = parse_bool(value).map_err(error)?,
+                        "move_while_engaged" => rules.can_move_while_engaged[index] = parse_bool(value).map_err(error)?,
+                        "range" => rules.range[index] = value.parse().ok().filter(|r: &Dim| (1..=MAX_RANGE).contains(r))
+                            .ok_or(error(anyhow!("invalid range (1 to {MAX_RANGE}): {value}")))?,
+                        "diagonal" => rules.diagonal[index] = parse_bool(value).map_err(error)?,
+                        "line_of_sight" => rules.line_of_sight[index] = value.parse().map_err(error)?,
+                        _ => return Err(error(anyhow!("unknown unit stat: {column}"))),
+                    }
+                } else if section == "effects" {
+                    let index = row_type.index();
+                    let amount = value.parse().map_err(|_| error(anyhow!("invalid amount: {value}")))?;
+                    match column.as_str() {
+                        "infect" => rules.infection[index] = amount,
+                        "infect_damage" => rules.infection_damage[index] = amount,
+                        "stun" => rules.stun[index] = amount,
+                        "shield" => rules.shield[index] = amount,
+                        _ => return Err(error(anyhow!("unknown effect: {column}"))),
+                    }
+                } else if let Some(table) = rules.table_mut(&section) {
+                    let column_type = parse_unit_type(column).map_err(error)?;
+                    table[row_type.index()][column_type.index()] = value.parse().map_err(|_| error(anyhow!("invalid amount: {value}")))?;
+                } else {
+                    return Err(error(anyhow!("unknown section: {section}")));
+                }
+            }
+        }
+        Ok(rules)
+    }
+}
+
+impl std::fmt::Display for RuleSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "[units]")?;
+        writeln!(f, "{:<10}{:>10}{:>10}{:>20}{:>10}{:>10}{:>15}", "type", "health", "move_back", "move_while_engaged",
+            "range", "diagonal", "line_of_sight")?;
+        let yes_no = |b: bool| if b { "yes" } else { "no" };
+        for unit_type in UnitType::all() {
+            writeln!(f, "{:<10}{:>10}{:>10}{:>20}{:>10}{:>10}{:>15}", unit_type.to_string(), self.initial_health(unit_type),
+                yes_no(self.can_move_back(unit_type)), yes_no(self.can_move_while_engaged(unit_type)),
+                self.range(unit_type), yes_no(self.can_attack_diagonally(unit_type)), self.line_of_sight(unit_type).to_string().to_ascii_lowercase())?;
+        }
+        writeln!(f)?;
+        Self::write_table(f, "damage", &self.damage)?;
+        writeln!(f)?;
+        Self::write_table(f, "repair", &self.repair)?;
+        writeln!(f)?;
+        Self::write_table(f, "self_destruct", &self.self_destruct)?;
+        writeln!(f)?;
+        writeln!(f, "[effects]")?;
+        writeln!(f, "{:<10}{:>10}{:>15}{:>10}{:>10}", "type", "infect", "infect_damage", "stun", "shield")?;
+        for unit_type in UnitType::all() {
+            writeln!(f, "{:<10}{:>10}{:>15}{:>10}{:>10}", unit_type.to_string(), self.infection(unit_type),
+                self.infection_damage(unit_type), self.stun(unit_type), self.shield(unit_type))?;
+        }
+        writeln!(f)?;
+        writeln!(f, "[terrain]")?;
+        writeln!(f, "{:<10}{:>20}{:>10}", "terrain", "damage_reduction", "repair")?;
+        for terrain in Terrain::all().filter(Terrain::is_passable) {
+            writeln!(f, "{:<10}{:>20}{:>10}", terrain.to_string(), self.damage_reduction(terrain), self.terrain_repair(terrain))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,234 @@
+use crate::{Coord, Dim, Health, Player, UnitType, RuleSet, Terrain};
+
+use anyhow::anyhow;
+
+// scenarios shipped with the game (selectable by name)
+pub const BUNDLED_SCENARIOS : &[(&str, &str)] = &[
+    ("classic", include_str!("../scenarios/classic.txt")),
+    ("doubled", include_str!("../scenarios/doubled.txt")),
+    ("virus-strike", include_str!("../scenarios/virus-strike.txt")),
+    ("last-stand", include_str!("../scenarios/last-stand.txt")),
+    ("corridor", include_str!("../scenarios/corridor.txt")),
+    ("fortress", include_str!("../scenarios/fortress.txt")),
+    ("four-corners", include_str!("../scenarios/four-corners.txt")),
+    ("bombardment", include_str!("../scenarios/bombardment.txt")),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScenarioUnit {
+    pub player: Player,
+    pub unit_type: UnitType,
+    pub coord: Coord,
+    pub health: Option<Health>,
+}
+
+// initial setup of a game: board dimensions, players, terrain, units and first player
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scenario {
+    pub rows: Dim,
+    pub cols: Dim,
+    pub players: Vec<Player>,
+    pub first_player: Player,
+    pub units: Vec<ScenarioUnit>,
+    pub terrain: Vec<(Coord,Terrain)>,
+}
+
+impl Scenario {
+    // the 6 unit armies of the attacker and defender in opposite corners
+    pub fn classic(rows: Dim, cols: Dim) -> Self {
+        Self::classic_for(rows, cols, &[Player::Attacker, Player::Defender])
+    }
+    // one 6 unit army per player in its own corner (see Player::forward)
+    pub fn classic_for(rows: Dim, cols: Dim, players: &[Player]) -> Self {
+        assert!(rows >= 4 && cols >= 4,"initial setup requires minimum of 4x4 board");
+        assert!(players.len() <= 2 || rows >= 6 && cols >= 6,"initial setup with more than 2 players requires minimum of 6x6 board");
+        use UnitType::*;
+        let offense = [
+            (0,0,AI),(0,1,Virus),(0,2,Program),
+            (1,0,Virus),(1,1,Firewall),
+            (2,0,Program),
+        ];
+        let defense = [
+            (0,0,AI),(0,1,Tech),(0,2,Firewall),
+            (1,0,Tech),(1,1,Program),
+            (2,0,Firewall),
+        ];
+        let players = Player::all().filter(|p| players.contains(p)).collect::<Vec<_>>();
+        let units = players.iter().flat_map(|&player| {
+            let army = if player.is_defender() { defense } else { offense };
+            // armies start in the corner they move away from
+            let (forward_row, forward_col) = player.forward();
+            army.into_iter().map(move|(row,col,unit_type)| {
+                let row = if forward_row < 0 { rows-1-row } else { row };
+                let col = if forward_col < 0 { cols-1-col } else { col };
+                ScenarioUnit { player, unit_type, coord: Coord::new(row,col), health: None }
+            })
+        }).collect();
+        Self {
+            rows,
+            cols,
+            first_player: players.first().copied().unwrap_or_default(),
+            players,
+            units,
+            terrain: Vec::new(),
+        }
+    }
+    pub fn bundled(name: &str) -> Option<Self> {
+        BUNDLED_SCENARIOS.iter()
+            .find(|(bundled_name,_)| *bundled_name == name)
+            .map(|(_,text)| text.parse().expect("bundled scenarios are valid"))
+    }
+    // a bundled scenario name or the path of a scenario file
+    pub fn load(name_or_path: &str) -> Result<Self,anyhow::Error> {
+        if let Some(scenario) = Self::bundled(name_or_path) {
+            return Ok(scenario);
+        }
+        let text = std::fs::read_to_string(name_or_path).map_err(|e| anyhow!("{name_or_path}: {e}"))?;
+        text.parse()
+    }
+    fn is_on_board(&self, coord: Coord) -> bool {
+        let (row, col) = coord.to_tuple();
+        row >= 0 && col >= 0 && row < self.rows && col < self.cols
+    }
+    fn terrain_at(&self, coord: Coord) -> Terrain {
+        self.terrain.iter().rev()
+            .find(|(terrain_coord,_)| *terrain_coord == coord)
+            .map(|&(_,terrain)| terrain)
+            .unwrap_or_default()
+    }
+    pub fn validate(&self, rules: &RuleSet) -> Result<(),anyhow::Error> {
+        if self.players.len() < 2 {
+            return Err(anyhow!("a game needs at least 2 players"));
+        }
+        if !self.players.contains(&self.first_player) {
+            return Err(anyhow!("first player {} is not one of the players", self.first_player));
+        }
+        if let Some((coord,_)) = self.terrain.iter().find(|(coord,_)| !self.is_on_board(*coord)) {
+            return Err(anyhow!("{} is outside of the {}x{} board", coord, self.rows, self.cols));
+        }
+        for (index, unit) in self.units.iter().enumerate() {
+            if !self.is_on_board(unit.coord) {
+                return Err(anyhow!("{} is outside of the {}x{} board", unit.coord, self.rows, self.cols));
+            }
+            if self.units[..index].iter().any(|other| other.coord == unit.coord) {
+                return Err(anyhow!("more than one unit at {}", unit.coord));
+            }
+            if !self.players.contains(&unit.player) {
+                return Err(anyhow!("{} at {} belongs to {} who is not one of the players", unit.unit_type, unit.coord, unit.player));
+            }
+            if !self.terrain_at(unit.coord).is_passable() {
+                return Err(anyhow!("{} at {} is on impassable terrain", unit.unit_type, unit.coord));
+            }
+            if let Some(health) = unit.health {
+                let max_health = rules.initial_health(unit.unit_type);
+                if health == 0 || health > max_health {
+                    return Err(anyhow!("health of {} at {} must be between 1 and {max_health}", unit.unit_type, unit.coord));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// board dimensions: "N" for a square board or "ROWSxCOLS"
+pub fn parse_dims(s: &str) -> Result<(Dim,Dim),anyhow::Error> {
+    let parse = |value: &str| value.trim().parse::<Dim>().ok().filter(|&d| d > 0)
+        .ok_or(anyhow!("invalid board dimensions: {s}"));
+    match s.split_once('x') {
+        Some((rows, cols)) => Ok((parse(rows)?, parse(cols)?)),
+        None => parse(s).map(|dim| (dim, dim)),
+    }
+}
+
+fn parse_player(name: &str) -> Result<Player,anyhow::Error> {
+    Player::all()
+        .find(|p| p.to_string().eq_ignore_ascii_case(name))
+        .ok_or(anyhow!("unknown player: {name}"))
+}
+
+// text format (see Display): "dim N" (or "dim ROWSxCOLS"), "players PLAYER..." (attacker and defender
+// plus any player with units if omitted), "first PLAYER", "TERRAIN COORD..." lines and one
+// "PLAYER TYPE COORD [HEALTH]" line per unit
+impl std::str::FromStr for Scenario {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut dims = None;
+        let mut players = None;
+        let mut first_player = Player::default();
+        let mut units = Vec::new();
+        let mut terrain = Vec::new();
+        for (line_index, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = |e: anyhow::Error| anyhow!("line {}: {e}", line_index+1);
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            match fields.as_slice() {
+                ["dim", value] => {
+                    dims = Some(parse_dims(value).map_err(error)?);
+                },
+                ["players", names @ ..] => {
+                    let names = names.iter().map(|name| parse_player(name)).collect::<Result<Vec<_>,_>>().map_err(error)?;
+                    players = Some(Player::all().filter(|p| names.contains(p)).collect::<Vec<_>>());
+                },
+                ["first", player] => {
+                    first_player = parse_player(player).map_err(error)?;
+                },
+                [name, coords @ ..] if name.parse::<Terrain>().is_ok() => {
+                    let name = name.parse::<Terrain>().map_err(error)?;
+                    for coord in coords {
+                        terrain.push((coord.parse::<Coord>().map_err(error)?, name));
+                    }
+                },
+                [player, unit_type, coord, health @ ..] if health.len() <= 1 => {
+                    let player = parse_player(player).map_err(error)?;
+                    let unit_type = UnitType::all()
+                        .find(|t| t.to_string().eq_ignore_ascii_case(unit_type))
+                        .ok_or(error(anyhow!("unknown unit type: {unit_type}")))?;
+                    let coord = coord.parse::<Coord>().map_err(error)?;
+                    let health = health.first()
+                        .map(|h| h.parse::<Health>().map_err(|_| error(anyhow!("invalid health: {h}"))))
+                        .transpose()?;
+                    units.push(ScenarioUnit { player, unit_type, coord, health });
+                },
+                _ => return Err(error(anyhow!("invalid line: {line}"))),
+            }
+        }
+        let (rows, cols) = dims.ok_or(anyhow!("missing board dimensions (dim N or dim ROWSxCOLS)"))?;
+        let players = players.unwrap_or_else(|| Player::all()
+            .filter(|p| [Player::Attacker, Player::Defender].contains(p) || units.iter().any(|unit| unit.player == *p))
+            .collect());
+        let scenario = Self { rows, cols, players, first_player, units, terrain };
+        scenario.validate(&RuleSet::default())?;
+        Ok(scenario)
+    }
+}
+
+impl std::fmt::Display for Scenario {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.rows == self.cols {
+            writeln!(f, "dim {}", self.rows)?;
+        } else {
+            writeln!(f, "dim {}x{}", self.rows, self.cols)?;
+        }
+        if self.players != [Player::Attacker, Player::Defender] {
+            let players = self.players.iter().map(|p| p.to_string().to_ascii_lowercase()).collect::<Vec<_>>();
+            writeln!(f, "players {}", players.join(" "))?;
+        }
+        writeln!(f, "first {}", self.first_player.to_string().to_ascii_lowercase())?;
+        // one line per run of the same terrain (keeps the order of the list)
+        for run in self.terrain.chunk_by(|(_,t1),(_,t2)| t1 == t2) {
+            let coords = run.iter().map(|(coord,_)| coord.to_string()).collect::<Vec<_>>();
+            writeln!(f, "{} {}", run[0].1.to_string().to_ascii_lowercase(), coords.join(" "))?;
+        }
+        for unit in &self.units {
+            write!(f, "{} {} {}", unit.player.to_string().to_ascii_lowercase(), unit.unit_type, unit.coord)?;
+            if let Some(health) = unit.health {
+                write!(f, " {health}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,194 @@
+use crate::{Game, Player, Action, Coord, UnitType, http::{self, Request, Response}};
+
+use std::io::Result as IoResult;
+use std::net::TcpListener;
+use std::sync::{Mutex, PoisonError};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+// self-contained browser interface (talks to the JSON endpoints below)
+const INDEX_HTML : &str = include_str!("../static/index.html");
+
+// css class and id of the board table, and the javascript function called on clicks
+const BOARD_CSS : &str = "board";
+const BOARD_CLICK : &str = "cellClick";
+
+// body of POST /api/play: an action in the explicit notation, a coordinate pair (the intent is
+// inferred as in the console) or a spawn
+#[derive(Debug, Default, Deserialize)]
+struct PlayRequest {
+    action: Option<String>,
+    from: Option<Coord>,
+    to: Option<Coord>,
+    spawn: Option<String>,
+    at: Option<Coord>,
+}
+
+struct Session {
+    game: Game,
+    // lines printed by the turns played so far
+    log: Vec<String>,
+}
+
+// local HTTP server for human-vs-AI and AI-vs-AI games in a browser:
+//   GET  /              the browser interface
+//   GET  /api/state     board, info, tables and log
+//   GET  /api/actions   legal actions (of the unit at ?row=R&col=C if given)
+//   POST /api/play      plays a human action ({"action":"M E3-D3"} or {"from":..,"to":..})
+//   POST /api/ai        the computer plays the current player
+//   GET  /api/suggest   action the computer would play, without playing it
+//   POST /api/reset     starts the game over
+// replies have the shape of the broker's: {"success":..,"error":..,"data":..}
+pub struct GameServer {
+    initial: Game,
+    humans: Vec<Player>,
+    session: Mutex<Session>,
+}
+
+fn success(data: Value) -> Response {
+    Response::json(200, &json!({ "success": true, "error": null, "data": data }))
+}
+
+fn failure(status: u16, error: impl std::fmt::Display) -> Response {
+    Response::json(status, &json!({ "success": false, "error": error.to_string(), "data": null }))
+}
+
+fn action_json(action: Action) -> Value {
+    let coords = action.into_coord_pair();
+    let to = match action {
+        Action::Spawn { at, .. } => Some(at),
+        _ => coords.map(|coords| coords.to),
+    };
+    json!({
+        "action": action.to_string(),
+        "kind": action.kind(),
+        "from": coords.map(|coords| coords.from),
+        "to": to,
+    })
+}
+
+impl Session {
+    fn log_output(&mut self, output: &[u8]) -> Vec<String> {
+        let lines = String::from_utf8_lossy(output).lines()
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect::<Vec<_>>();
+        self.log.extend(lines.iter().cloned());
+        lines
+    }
+}
+
+impl GameServer {
+    // the computer plays every player not in humans
+    pub fn new(game: Game, humans: Vec<Player>) -> Self {
+        let session = Mutex::new(Session { game: game.clone(), log: Vec::new() });
+        Self { initial: game, humans, session }
+    }
+    fn state(&self, session: &Session) -> Value {
+        let game = &session.game;
+        let viewer = game.viewer_for(&self.humans);
+        let mut info = Vec::new();
+        game.pretty_print_info(&mut info).expect("no errors writing to a vector");
+        json!({
+            "player": game.player().to_string(),
+            "moves": game.total_moves(),
+            "result": game.end_game_message(),
+            "humans": self.humans.iter().map(Player::to_string).collect::<Vec<_>>(),
+            "human_turn": self.humans.contains(&game.player()),
+            "reinforcements": game.reinforcements().is_some(),
+            "action_points": game.action_points().is_some(),
+            "spawn_types": Game::spawnable_unit_types().map(|unit_type| unit_type.to_string()).collect::<Vec<_>>(),
+            "board": game.to_html_board_string_for(BOARD_CSS.to_string(), BOARD_CSS.to_string(), BOARD_CLICK.to_string(), viewer),
+            "info": String::from_utf8_lossy(&info),
+            "damage_table": game.html_damage_table_string(Some("from / to")),
+            "repair_table": game.html_repair_table_string(Some("from / to")),
+            "log": session.log,
+        })
+    }
+    fn actions(&self, session: &Session, request: &Request) -> Response {
+        let game = &session.game;
+        if game.end_game_result().is_some() {
+            return success(json!([]));
+        }
+        let coord = |key| request.query(key).and_then(|value| value.parse().ok());
+        let actions = match (coord("row"), coord("col")) {
+            (Some(row), Some(col)) if game.is_valid_position(Coord::new(row, col)) =>
+                game.possible_actions_from_coord(Coord::new(row, col)).collect::<Vec<_>>(),
+            (Some(row), Some(col)) => return failure(400, format!("not on the board: row {row}, col {col}")),
+            (None, None) => std::iter::once(Action::Pass).chain(game.possible_actions()).collect(),
+            _ => return failure(400, "row and col go together"),
+        };
+        success(actions.into_iter().map(action_json).collect())
+    }
+    fn parse_play(game: &Game, body: &str) -> Result<Action,String> {
+        let play = serde_json::from_str::<PlayRequest>(body).map_err(|error| error.to_string())?;
+        match play {
+            PlayRequest { action: Some(action), .. } => game.parse_action(&action).map_err(|error| error.to_string()),
+            PlayRequest { from: Some(from), to: Some(to), .. } => game.action_from_coords(from, to).map_err(|reason| reason.to_string()),
+            PlayRequest { spawn: Some(name), at: Some(at), .. } => {
+                let unit_type = UnitType::all().find(|t| t.to_string().eq_ignore_ascii_case(&name))
+                    .ok_or(format!("unknown unit type: {name}"))?;
+                Ok(Action::Spawn { at, unit_type })
+            },
+            _ => Err(String::from("expected an action, from and to, or spawn and at")),
+        }
+    }
+    fn play(&self, session: &mut Session, body: &str) -> Response {
+        if session.game.end_game_result().is_some() {
+            return failure(409, "the game is over");
+        }
+        let action = match Self::parse_play(&session.game, body) {
+            Ok(action) => action,
+            Err(error) => return failure(400, format!("Invalid move: {error}")),
+        };
+        let mut output = Vec::new();
+        match session.game.human_play_turn_from_action(Some(&mut output), action).expect("no errors writing to a vector") {
+            Ok(()) => {
+                let lines = session.log_output(&output);
+                success(json!({ "action": action_json(action), "log": lines, "state": self.state(session) }))
+            },
+            Err(reason) => failure(400, format!("Invalid move: {reason}")),
+        }
+    }
+    fn ai(&self, session: &mut Session) -> Response {
+        if session.game.end_game_result().is_some() {
+            return failure(409, "the game is over");
+        }
+        let mut output = Vec::new();
+        let action = session.game.computer_play_turn(Some(&mut output)).expect("no errors writing to a vector");
+        let lines = session.log_output(&output);
+        success(json!({ "action": action.map(action_json), "log": lines, "state": self.state(session) }))
+    }
+    fn suggest(&self, session: &Session) -> Response {
+        if session.game.end_game_result().is_some() {
+            return failure(409, "the game is over");
+        }
+        let mut game = session.game.clone();
+        let (score, action, seconds, _) = game.suggest_action();
+        success(json!({ "action": action.map(action_json), "score": score, "seconds": seconds }))
+    }
+    pub fn handle(&self, request: &Request) -> Response {
+        // a request that panicked doesn't lock the others out of the session
+        let mut session = self.session.lock().unwrap_or_else(PoisonError::into_inner);
+        match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/") => Response::html(INDEX_HTML),
+            ("GET", "/api/state") => success(self.state(&session)),
+            ("GET", "/api/actions") => self.actions(&session, request),
+            ("POST", "/api/play") => self.play(&mut session, &request.body),
+            ("POST", "/api/ai") => self.ai(&mut session),
+            ("GET", "/api/suggest") => self.suggest(&session),
+            ("POST", "/api/reset") => {
+                *session = Session { game: self.initial.clone(), log: Vec::new() };
+                success(self.state(&session))
+            },
+            (_, "/" | "/api/state" | "/api/actions" | "/api/play" | "/api/ai" | "/api/suggest" | "/api/reset") =>
+                failure(405, format!("method not allowed: {}", request.method)),
+            (_, path) => failure(404, format!("not found: {path}")),
+        }
+    }
+    // answers requests until the listener fails (bind to port 0 for any free port)
+    pub fn serve(self, listener: TcpListener) -> IoResult<()> {
+        http::serve(listener, move |request| self.handle(request))
+    }
+}
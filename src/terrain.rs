@@ -0,0 +1,49 @@
+use enum_iterator::Sequence;
+
+use anyhow::anyhow;
+
+#[derive(Debug, PartialEq, Eq, Default, Clone, Copy, Hash, Sequence)]
+#[derive(derive_more::Display)]
+pub enum Terrain {
+    #[default]
+    Plain,
+    // impassable, no unit can stand on it
+    Wall,
+    // incoming damage is reduced (see RuleSet::damage_reduction)
+    Fortified,
+    // the unit standing on it is repaired each turn (see RuleSet::terrain_repair)
+    Depot,
+    // a unit standing on it can't leave while engaged (whatever its type)
+    Chokepoint,
+}
+
+impl Terrain {
+    pub fn all() -> enum_iterator::All<Self> {
+        enum_iterator::all()
+    }
+    pub fn is_passable(&self) -> bool {
+        *self != Self::Wall
+    }
+    // prefix of the cell in position strings and the board printer (plain has none)
+    pub const fn marker(&self) -> Option<char> {
+        match self {
+            Self::Plain => None,
+            Self::Wall => Some('#'),
+            Self::Fortified => Some('+'),
+            Self::Depot => Some('*'),
+            Self::Chokepoint => Some('='),
+        }
+    }
+    pub fn from_marker(marker: char) -> Option<Self> {
+        Self::all().find(|t| t.marker() == Some(marker))
+    }
+}
+
+impl std::str::FromStr for Terrain {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::all()
+            .find(|t| t.to_string().eq_ignore_ascii_case(s))
+            .ok_or(anyhow!("unknown terrain: {s}"))
+    }
+}
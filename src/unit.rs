@@ -1,9 +1,10 @@
-use crate::{UnitType, Health, MAX_HEALTH};
+use crate::{UnitType, Health, MAX_HEALTH, RuleSet, Effects, InflictedEffects};
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct Unit {
     pub (crate) unit_type : UnitType,
     pub (crate) health : Health,
+    pub (crate) effects : Effects,
 }
 
 impl Default for Unit {
@@ -14,20 +15,22 @@ impl Default for Unit {
 
 impl Unit {
     pub fn new(unit_type : UnitType) -> Self {
-        let health = unit_type.initial_health();
+        Self::with_health(unit_type, unit_type.initial_health())
+    }
+    pub fn with_health(unit_type : UnitType, health: Health) -> Self {
         assert!(health <= MAX_HEALTH);
-        Self { unit_type, health }
+        Self { unit_type, health, effects: Default::default() }
     }
-    pub fn can_repair(&self, target: &Self) -> bool {
+    pub fn can_repair(&self, target: &Self, rules: &RuleSet) -> bool {
         assert!(target.health <= MAX_HEALTH);
-        let repair = self.unit_type.repair_amount(&target.unit_type);
-        let max_health = target.unit_type.initial_health();
+        let repair = rules.repair_amount(self.unit_type, target.unit_type);
+        let max_health = rules.initial_health(target.unit_type);
         repair != 0 && target.health != max_health
     }
-    pub fn apply_repair(&mut self, target: &mut Self) -> u8 {
+    pub fn apply_repair(&mut self, target: &mut Self, rules: &RuleSet) -> u8 {
         assert!(target.health <= MAX_HEALTH);
-        let repair = self.unit_type.repair_amount(&target.unit_type);
-        let max_health = target.unit_type.initial_health();
+        let repair = rules.repair_amount(self.unit_type, target.unit_type);
+        let max_health = rules.initial_health(target.unit_type);
         if repair + target.health < max_health {
             target.health += repair;
         } else {
@@ -35,14 +38,18 @@ impl Unit {
         }
         repair
     }
-    pub fn can_damage(&self, target: &Self) -> bool {
+    pub fn can_damage(&self, target: &Self, rules: &RuleSet) -> bool {
         assert!(target.health <= MAX_HEALTH);
-        let damage = self.unit_type.damage_amount(&target.unit_type);
+        let damage = rules.damage_amount(self.unit_type, target.unit_type);
         damage != 0
     }
-    pub fn apply_damage(&mut self, target: &mut Self) -> Health {
+    // reduction comes from the terrain the target stands on
+    pub fn apply_damage(&mut self, target: &mut Self, rules: &RuleSet, reduction: Health) -> Health {
         assert!(target.health <= MAX_HEALTH);
-        let damage = self.unit_type.damage_amount(&target.unit_type);
+        let damage = rules.damage_amount(self.unit_type, target.unit_type).saturating_sub(reduction);
+        if target.absorb_hit(damage, rules) {
+            return 0;
+        }
         if damage < target.health {
             target.health -= damage;
         } else {
@@ -50,9 +57,12 @@ impl Unit {
         }
         damage
     }
-    pub fn apply_self_destruct(&mut self, target: &mut Self) -> Health {
+    pub fn apply_self_destruct(&mut self, target: &mut Self, rules: &RuleSet, reduction: Health) -> Health {
         assert!(target.health <= MAX_HEALTH);
-        let damage = self.unit_type.self_destruct_amount(&target.unit_type);
+        let damage = rules.self_destruct_amount(self.unit_type, target.unit_type).saturating_sub(reduction);
+        if target.absorb_hit(damage, rules) {
+            return 0;
+        }
         if damage < target.health {
             target.health -= damage;
         } else {
@@ -60,17 +70,64 @@ impl Unit {
         }
         damage
     }
+    pub fn has_shield(&self, rules: &RuleSet) -> bool {
+        rules.shield(self.unit_type) > 0 && self.effects.shield_recharge == 0
+    }
+    // a shield takes the whole hit and needs some turns to come back
+    fn absorb_hit(&mut self, damage: Health, rules: &RuleSet) -> bool {
+        if damage > 0 && self.has_shield(rules) {
+            self.effects.shield_recharge = rules.shield(self.unit_type);
+            true
+        } else {
+            false
+        }
+    }
+    // effects of a hit that got through (see RuleSet::infection and RuleSet::stun)
+    pub fn inflict_effects(&self, target: &mut Self, rules: &RuleSet) -> InflictedEffects {
+        let (infection, stun) = (rules.infection(self.unit_type), rules.stun(self.unit_type));
+        if infection > 0 {
+            target.effects.infection = Some((self.unit_type, infection));
+        }
+        target.effects.stun = target.effects.stun.max(stun);
+        InflictedEffects { absorbed: false, infection, stun }
+    }
+    // end of the turn of the unit's player: effects wear off by a turn and infections
+    // deal their damage (returned)
+    pub fn tick_effects(&mut self, rules: &RuleSet) -> Health {
+        let effects = &mut self.effects;
+        let mut damage = 0;
+        if let Some((source, turns)) = effects.infection {
+            damage = rules.infection_damage(source).min(self.health);
+            self.health -= damage;
+            effects.infection = (turns > 1).then_some((source, turns - 1));
+        }
+        effects.stun = effects.stun.saturating_sub(1);
+        effects.shield_recharge = effects.shield_recharge.saturating_sub(1);
+        damage
+    }
+    pub fn effects(&self) -> &Effects {
+        &self.effects
+    }
+    pub fn is_stunned(&self) -> bool {
+        self.effects.stun > 0
+    }
+    pub fn unit_type(&self) -> UnitType {
+        self.unit_type
+    }
+    pub fn health(&self) -> Health {
+        self.health
+    }
     pub fn kill(&mut self) {
-        self.health = 0; 
+        self.health = 0;
     }
-    pub fn can_move_back(&self) -> bool {
-        self.unit_type.can_move_back()
+    pub fn can_move_back(&self, rules: &RuleSet) -> bool {
+        rules.can_move_back(self.unit_type)
     }
-    pub fn can_move_while_engaged(&self) -> bool {
-        self.unit_type.can_move_while_engaged()
+    pub fn can_move_while_engaged(&self, rules: &RuleSet) -> bool {
+        rules.can_move_while_engaged(self.unit_type)
     }
-    pub fn initial_health(&self) -> Health {
-        self.unit_type.initial_health()
+    pub fn initial_health(&self, rules: &RuleSet) -> Health {
+        rules.initial_health(self.unit_type)
     }
 }
 
@@ -1,7 +1,11 @@
-use crate::{DisplayFirstLetter, Health};
+use crate::{DisplayFirstLetter, Dim, Health, RuleSet, rules::LineOfSight};
 use enum_iterator::Sequence;
 
-#[derive(Debug, PartialEq, PartialOrd,Default, Clone, Copy, Sequence)]
+#[cfg(feature="serde")]
+use serde::{Serialize, Deserialize};
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Default, Clone, Copy, Sequence)]
 #[derive(derive_more::Display)]
 pub enum UnitType {
     AI,
@@ -10,17 +14,30 @@ pub enum UnitType {
     Firewall,
     #[default]
     Program,
+    // ranged unit (attacks from a distance without retaliation from melee units)
+    Mortar,
 }
 
 impl DisplayFirstLetter for UnitType {}
 
+// named column of a stats table computed from the source unit type
+pub type StatsColumn<'a> = (&'a str, &'a dyn Fn (&UnitType) -> String);
+
 impl UnitType {
     pub fn all() -> enum_iterator::All<Self> {
         enum_iterator::all()
     }
+    pub const fn cardinality() -> usize {
+        enum_iterator::cardinality::<Self>()
+    }
+    pub const fn index(&self) -> usize {
+        *self as usize
+    }
+    // extra columns describe the source unit (after the targets)
     pub fn stats_table(
             legend: Option<&str>, 
             stat_fn: impl Fn (&Self,&Self) -> Health,
+            extra_columns: &[StatsColumn],
             format_header: impl Fn (&String) -> String,
             format_data: impl Fn (&String) -> String,
         ) -> Vec<Vec<String>> {
@@ -31,30 +48,36 @@ impl UnitType {
             vec![format_header(&"".to_string())]
         };
         targets.extend(Self::all().map(|t|format_header(&t.to_string())));
+        targets.extend(extra_columns.iter().map(|(name,_)|format_header(&name.to_string())));
         result.push(targets);
         for source in Self::all() {
             let mut targets = vec![format_header(&source.to_string())];
             if Self::all().map(|t|stat_fn(&source,&t)).sum::<Health>() != 0 {
                 targets.extend(Self::all().map(|t|format_data(&stat_fn(&source,&t).to_string())));
+                targets.extend(extra_columns.iter().map(|(_,column_fn)|format_data(&column_fn(&source))));
                 result.push(targets);
             }
         }
         result
     }
     pub fn damage_table(
+        rules: &RuleSet,
         legend: Option<&str>, 
         format_header: impl Fn (&String) -> String,
         format_data: impl Fn (&String) -> String,
     ) -> Vec<Vec<String>> {
-        Self::stats_table(legend, Self::damage_amount, format_header, format_data)
+        let range = |s: &Self| rules.range_string(*s);
+        Self::stats_table(legend, |s,t| rules.damage_amount(*s,*t), &[("range", &range)], format_header, format_data)
     }
     pub fn repair_table(
+        rules: &RuleSet,
         legend: Option<&str>, 
         format_header: impl Fn (&String) -> String,
         format_data: impl Fn (&String) -> String,
     ) -> Vec<Vec<String>> {
-        Self::stats_table(legend, Self::repair_amount, format_header, format_data)
+        Self::stats_table(legend, |s,t| rules.repair_amount(*s,*t), &[], format_header, format_data)
     }
+    // built-in values (the game consults its RuleSet, which defaults to these)
     pub fn can_move_back(&self) -> bool {
         use UnitType::*;
         matches!(self, Virus | Tech)
@@ -66,6 +89,26 @@ impl UnitType {
     pub fn initial_health(&self) -> Health {
         9
     }
+    // maximum attack distance along a straight line (or a diagonal if allowed)
+    pub fn range(&self) -> Dim {
+        use UnitType::*;
+        match self {
+            Mortar => 3,
+            _ => 1,
+        }
+    }
+    pub fn can_attack_diagonally(&self) -> bool {
+        use UnitType::*;
+        matches!(self, Mortar)
+    }
+    // what blocks attacks at a distance (shells fly over units but not over walls)
+    pub fn line_of_sight(&self) -> LineOfSight {
+        use UnitType::*;
+        match self {
+            Mortar => LineOfSight::Walls,
+            _ => LineOfSight::Units,
+        }
+    }
     pub fn damage_amount(&self, target: &Self) -> Health {
         use UnitType::*;
         match self {
@@ -75,7 +118,7 @@ impl UnitType {
             },
             Virus => match target {
                 AI => 9,
-                Tech | Program => 6,
+                Tech | Program | Mortar => 6,
                 Virus | Firewall => 1,
             },
             Tech => match target {
@@ -87,13 +130,17 @@ impl UnitType {
                 Firewall => 1,
                 _ => 3,
             },
+            Mortar => match target {
+                Firewall => 1,
+                _ => 2,
+            },
         }
     }
     pub fn repair_amount(&self, target: &Self) -> Health {
         use UnitType::*;
         match self {
             Tech => match target {
-                AI | Firewall | Program => 3,
+                AI | Firewall | Program | Mortar => 3,
                 _ => 0,
             },
             AI  => match target {
@@ -106,8 +153,4 @@ impl UnitType {
     pub fn self_destruct_amount(&self, _target: &Self) -> Health {
         2
     }
-    pub fn self_destruct_string() -> String {
-        // same for every unit (TODO: we should check this)
-        UnitType::AI.self_destruct_amount(&UnitType::AI).to_string()
-    }
 }
\ No newline at end of file
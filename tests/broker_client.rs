@@ -0,0 +1,182 @@
+#![cfg(all(feature="broker", feature="server"))]
+
+use ai_wargame::{Action, BrokerError, Coord, Game, GameOptions, Player, UnitType, game::ActionPoints, WargameError, broker_server::BrokerServer, http::{self, Request, Response}, protocol::{BrokerData, BrokerResult}};
+
+use std::net::{SocketAddr, TcpListener};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+fn start(handler: impl Fn(&Request) -> Response + Send + Sync + 'static) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("free local port");
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || http::serve(listener, handler));
+    addr
+}
+
+fn client(addr: SocketAddr, options: GameOptions) -> Game {
+    Game::new(GameOptions { broker: Some(format!("http://{addr}/test")), broker_min_delay: 0.01, broker_max_delay: 0.05, ..options })
+}
+
+// plays an action (the first unit move without one) and posts it
+fn play_and_post(game: &mut Game, action: Option<Action>) -> Action {
+    let action = action.unwrap_or_else(|| game.possible_actions().find(|action| action.kind() == "move").expect("a unit can move"));
+    game.play_turn_from_action(action).expect("legal action");
+    game.broker_post_move(action).expect("action accepted");
+    action
+}
+
+fn start_broker() -> SocketAddr {
+    let broker = BrokerServer::new();
+    start(move |request| broker.handle(request))
+}
+
+#[test]
+fn transient_failures_are_retried_with_backoff() {
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+    let broker = BrokerServer::new();
+    let addr = start(move |request| match counter.fetch_add(1, Ordering::SeqCst) {
+        0 | 1 => Response::new(503, "text/plain", "busy"),
+        _ => broker.handle(request),
+    });
+    let game = client(addr, GameOptions::default());
+    assert_eq!(game.broker_get_move().expect("third request succeeds"), None);
+    // the latest move (a finished game on the channel?) then the move of turn 1
+    assert_eq!(requests.load(Ordering::SeqCst), 4);
+    assert!(game.broker_backoff(0) < game.broker_backoff(1));
+    assert_eq!(game.broker_backoff(10), Duration::from_secs_f32(0.05));
+
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+    let addr = start(move |_| { counter.fetch_add(1, Ordering::SeqCst); Response::new(503, "text/plain", "busy") });
+    let game = client(addr, GameOptions { broker_max_failures: 3, ..Default::default() });
+    assert!(matches!(game.broker_get_move(), Err(WargameError::Broker(BrokerError::Status(503)))));
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn slow_brokers_time_out() {
+    let addr = start(|_| {
+        std::thread::sleep(Duration::from_secs(2));
+        Response::new(200, "application/json", r#"{"success":true,"error":null,"data":null}"#)
+    });
+    let game = client(addr, GameOptions { broker_timeout: 0.2, broker_max_failures: 1, ..Default::default() });
+    let error = game.broker_get_move().expect_err("no reply in time");
+    assert!(matches!(error, WargameError::Broker(BrokerError::Timeout(_))), "{error:?}");
+    assert!(error.to_string().starts_with("broker request timed out"));
+}
+
+#[test]
+fn missing_moves_are_replayed() {
+    let addr = start_broker();
+    let mut game = client(addr, GameOptions::default());
+    let first = play_and_post(&mut game, None);
+    let second = play_and_post(&mut game, Some(Action::Pass));
+    // a client that restarted (or lost replies) catches up with the broker
+    let mut late = client(addr, GameOptions::default());
+    let played = late.broker_resync().expect("broker reachable");
+    assert_eq!(played.iter().map(|(_, action, _)| *action).collect::<Vec<_>>(), vec![first, second]);
+    assert_eq!(late.total_moves(), 2);
+    assert_eq!(late.player(), game.player());
+    assert!(late.broker_resync().expect("broker reachable").is_empty());
+    // the next move is waited for until another client posts it
+    let waiting = std::thread::spawn(move || late.broker_wait_move());
+    std::thread::sleep(Duration::from_millis(100));
+    let third = play_and_post(&mut game, None);
+    assert_eq!(waiting.join().unwrap().expect("move posted"), third);
+}
+
+#[test]
+fn finished_games_are_not_replayed() {
+    let addr = start_broker();
+    let mut previous = client(addr, GameOptions::default());
+    play_and_post(&mut previous, None);
+    previous.set_deadlock(true);
+    previous.broker_post_result().expect("result accepted");
+    // a new game on the same channel waits for its own first action
+    let mut defender = client(addr, GameOptions::default());
+    assert!(defender.broker_resync().expect("broker reachable").is_empty());
+    assert_eq!(defender.broker_get_move().expect("broker reachable"), None);
+    let mut attacker = client(addr, GameOptions::default());
+    let first = play_and_post(&mut attacker, Some(Action::Pass));
+    assert_eq!(defender.broker_get_move().expect("broker reachable"), Some(first));
+}
+
+#[test]
+fn brokers_ahead_of_the_game_are_detected() {
+    // a broker without turn queries that is already at turn 5
+    let addr = start(|_| Response::new(200, "application/json",
+        r#"{"success":true,"error":null,"data":{"from":{"row":0,"col":0},"to":{"row":0,"col":1},"turn":5}}"#));
+    let mut game = client(addr, GameOptions::default());
+    let error = game.broker_get_move().expect_err("turn 1 expected");
+    assert!(matches!(error, WargameError::Broker(BrokerError::Desync { expected: 1, got: 5 })), "{error:?}");
+    assert_eq!(error.to_string(), "broker out of sync: expected turn 1, got turn 5");
+    assert!(matches!(game.broker_resync(), Err(WargameError::Broker(BrokerError::Desync { expected: 1, got: 5 }))));
+    assert_eq!(game.total_moves(), 0);
+}
+
+#[test]
+fn every_action_kind_goes_through_the_broker() {
+    let (from, to) = (Coord::new(2, 3), Coord::new(1, 3));
+    let actions = [Action::Pass, Action::EndTurn, Action::Move { from, to }, Action::Repair { from, to }, Action::Attack { from, to },
+        Action::SelfDestruct { from }, Action::Spawn { at: to, unit_type: UnitType::Virus }];
+    for action in actions {
+        let json = serde_json::to_string(&BrokerData::new(action, 3)).unwrap();
+        let data = serde_json::from_str::<BrokerData>(&json).unwrap();
+        assert_eq!((data.version, data.turn, data.action()), (2, 3, Some(action)), "{json}");
+    }
+    assert_eq!(serde_json::to_value(BrokerData::new(Action::SelfDestruct { from }, 1)).unwrap()["to"], serde_json::json!({ "row": 2, "col": 3 }));
+    // version 1 moves are interpreted from the cells
+    let data = serde_json::from_str::<BrokerData>(r#"{"from":{"row":2,"col":4},"to":{"row":1,"col":4},"turn":1}"#).unwrap();
+    assert_eq!((data.version, data.kind, data.action()), (1, None, None));
+
+    let addr = start_broker();
+    let mut game = client(addr, GameOptions::default());
+    let remote = client(addr, GameOptions::default());
+    play_and_post(&mut game, Some(Action::Pass));
+    assert_eq!(remote.broker_get_move().expect("broker reachable"), Some(Action::Pass));
+}
+
+#[test]
+fn every_action_of_a_turn_is_posted() {
+    let options = || GameOptions { action_points: Some(ActionPoints::PerTurn(2)), ..Default::default() };
+    let addr = start_broker();
+    let mut attacker = client(addr, options());
+    let mut remote = client(addr, options());
+    let first = play_and_post(&mut attacker, None);
+    let end = play_and_post(&mut attacker, Some(Action::EndTurn));
+    assert_eq!((attacker.total_moves(), attacker.total_actions()), (1, 2));
+    for action in [first, end] {
+        assert_eq!(remote.broker_get_move().expect("broker reachable"), Some(action));
+        remote.play_turn_from_action(action).expect("legal action");
+    }
+    assert_eq!(remote.broker_get_move().expect("broker reachable"), None);
+    let mut late = client(addr, options());
+    assert_eq!(late.broker_resync().expect("broker reachable").len(), 2);
+    assert_eq!((late.total_moves(), late.player()), (1, Player::Defender));
+}
+
+#[test]
+fn results_are_reported_at_the_end() {
+    let broker = Arc::new(BrokerServer::new());
+    let server = broker.clone();
+    let addr = start(move |request| server.handle(request));
+    let mut attacker = client(addr, GameOptions::default());
+    let mut defender = client(addr, GameOptions::default());
+    // nothing to report before the end
+    attacker.broker_post_result().expect("game not over");
+    let action = play_and_post(&mut attacker, None);
+    defender.play_turn_from_action(defender.broker_wait_move().expect("move posted")).expect("legal action");
+    // the defender can't act: both clients see the deadlock and report the same result
+    attacker.set_deadlock(true);
+    defender.set_deadlock(true);
+    defender.broker_post_result().expect("result accepted");
+    attacker.broker_post_result().expect("same result accepted");
+    assert_eq!(broker.moves("test").last().map(|data| (data.turn, data.result)), Some((1, Some(BrokerResult::Attacker))));
+    // the move cap makes the defender win instead
+    let mut other = client(addr, GameOptions { max_moves: Some(1), ..Default::default() });
+    other.play_turn_from_action(action).expect("legal action");
+    let error = other.broker_post_result().expect_err("another result");
+    assert_eq!(error.to_string(), "broker error: another result was reported for turn 1");
+}
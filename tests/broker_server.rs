@@ -0,0 +1,120 @@
+#![cfg(feature="server")]
+
+use ai_wargame::broker_server::BrokerServer;
+use serde_json::{json, Value};
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+
+fn start() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("free local port");
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || BrokerServer::new().serve(listener));
+    addr
+}
+
+// status and json reply of a request to the local broker
+fn request(addr: SocketAddr, method: &str, path: &str, body: Option<Value>) -> (u16, Value) {
+    let mut stream = TcpStream::connect(addr).expect("broker listening");
+    let body = body.map(|body| body.to_string()).unwrap_or_default();
+    write!(stream, "{method} {path} HTTP/1.1\r\nHost: {addr}\r\nContent-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").expect("complete response");
+    let status = head.split_whitespace().nth(1).and_then(|status| status.parse().ok()).expect("status code");
+    (status, serde_json::from_str(body).expect("json reply"))
+}
+
+fn data(from: (i8, i8), to: (i8, i8), turn: usize) -> Value {
+    json!({ "from": { "row": from.0, "col": from.1 }, "to": { "row": to.0, "col": to.1 }, "turn": turn })
+}
+
+#[test]
+fn channels_relay_moves_in_turn_order() {
+    let addr = start();
+    assert_eq!(request(addr, "GET", "/test", None), (200, json!({ "success": true, "error": null, "data": null })));
+    let first = data((2, 4), (1, 4), 1);
+    assert_eq!(request(addr, "POST", "/test", Some(first.clone())), (200, json!({ "success": true, "error": null, "data": first })));
+    assert_eq!(request(addr, "GET", "/test", None).1["data"], first);
+    let second = data((0, 0), (1, 0), 2);
+    assert_eq!(request(addr, "POST", "/test", Some(second.clone())).0, 200);
+    assert_eq!(request(addr, "GET", "/test", None).1["data"], second);
+    assert_eq!(request(addr, "GET", "/test?turn=1", None).1["data"], first);
+    assert_eq!(request(addr, "GET", "/test?turn=3", None).1["data"], Value::Null);
+    // other channels are separate games
+    assert_eq!(request(addr, "GET", "/other", None).1["data"], Value::Null);
+}
+
+#[test]
+fn turns_out_of_order_are_rejected() {
+    let addr = start();
+    let (status, reply) = request(addr, "POST", "/game", Some(data((2, 4), (1, 4), 2)));
+    assert_eq!(status, 404);
+    assert_eq!(reply["success"], false);
+    assert_eq!(reply["error"], "expected turn 1, got turn 2");
+    let first = data((2, 4), (1, 4), 1);
+    request(addr, "POST", "/game", Some(first.clone()));
+    // the same move again is a retry, another move for the same turn is not
+    assert_eq!(request(addr, "POST", "/game", Some(first)).0, 200);
+    assert_eq!(request(addr, "POST", "/game", Some(data((2, 3), (1, 3), 1))).0, 200, "turn 1 starts a new game");
+    assert_eq!(request(addr, "POST", "/game", Some(data((0, 0), (0, 1), 1))).1["data"]["from"], json!({ "row": 0, "col": 0 }));
+    assert_eq!(request(addr, "POST", "/game", Some(data((0, 0), (0, 2), 3))).0, 404);
+}
+
+#[test]
+fn retries_of_the_first_move_keep_the_game() {
+    let addr = start();
+    let first = data((2, 4), (1, 4), 1);
+    let second = data((0, 0), (1, 0), 2);
+    request(addr, "POST", "/game", Some(first.clone()));
+    request(addr, "POST", "/game", Some(second.clone()));
+    // the reply to the first move was lost and the client sends it again
+    assert_eq!(request(addr, "POST", "/game", Some(first.clone())).0, 200);
+    assert_eq!(request(addr, "GET", "/game?turn=2", None).1["data"], second);
+    // once the game is over, the same first move starts the next game
+    request(addr, "POST", "/game", Some(json!({ "turn": 2, "result": "attacker" })));
+    assert_eq!(request(addr, "POST", "/game", Some(first.clone())).0, 200);
+    assert_eq!(request(addr, "GET", "/game", None).1["data"], first);
+    assert_eq!(request(addr, "GET", "/game?turn=2", None).1["data"], Value::Null);
+}
+
+#[test]
+fn invalid_requests_get_error_replies() {
+    let addr = start();
+    let (status, reply) = request(addr, "POST", "/test", Some(json!({ "from": "C4" })));
+    assert_eq!(status, 404);
+    assert!(reply["error"].as_str().unwrap().starts_with("invalid move data"));
+    assert_eq!(request(addr, "GET", "/", None).0, 404);
+    assert_eq!(request(addr, "GET", "/test?turn=0", None).1["error"], "invalid turn: 0");
+    assert_eq!(request(addr, "DELETE", "/test", None).0, 404);
+}
+
+#[test]
+fn actions_and_results_are_kept() {
+    let addr = start();
+    request(addr, "POST", "/game", Some(data((2, 4), (1, 4), 1)));
+    let pass = json!({ "version": 2, "action": "pass", "turn": 2 });
+    assert_eq!(request(addr, "POST", "/game", Some(pass.clone())).1["data"], pass);
+    assert_eq!(request(addr, "GET", "/game?turn=1", None).1["data"], data((2, 4), (1, 4), 1), "version 1 moves are relayed as they came");
+    let (status, reply) = request(addr, "POST", "/game", Some(json!({ "version": 2, "turn": 1, "result": "defender" })));
+    assert_eq!((status, reply["error"].as_str()), (404, Some("no move for a result at turn 1")));
+    let result = json!({ "version": 2, "turn": 2, "result": "defender" });
+    assert_eq!(request(addr, "POST", "/game", Some(result.clone())).1["data"]["result"], "defender");
+    assert_eq!(request(addr, "POST", "/game", Some(result)).0, 200, "every client may report the result");
+    assert_eq!(request(addr, "GET", "/game", None).1["data"], json!({ "version": 2, "action": "pass", "turn": 2, "result": "defender" }));
+    assert_eq!(request(addr, "POST", "/game", Some(json!({ "version": 2, "turn": 2, "result": "draw" }))).0, 404);
+}
+
+#[cfg(feature="broker")]
+#[test]
+fn game_clients_exchange_moves() {
+    use ai_wargame::{Coord, Game, GameOptions};
+    let addr = start();
+    let broker = Some(format!("http://{addr}/match"));
+    let mut attacker = Game::new(GameOptions { broker: broker.clone(), ..Default::default() });
+    let defender = Game::new(GameOptions { broker, ..Default::default() });
+    assert_eq!(defender.broker_get_move().expect("broker reachable"), None);
+    let (_, action, _) = attacker.play_turn_from_coords(Coord::new(2, 4), Coord::new(1, 4)).expect("legal move");
+    attacker.broker_post_move(action).expect("move accepted");
+    assert_eq!(defender.broker_get_move().expect("broker reachable"), Some(action));
+}
@@ -0,0 +1,39 @@
+// fixtures shared by the integration tests (each test file uses some of them)
+#![allow(dead_code)]
+
+use ai_wargame::{Coord, Game, GameOptions, Scenario};
+
+// both AIs with an attacker Program and Tech next to a defender Virus
+pub const SCENARIO : &str = "dim 5\nattacker AI E4\nattacker Program C2\nattacker Tech D2\ndefender AI A0\ndefender Virus B2";
+
+pub fn coord(s: &str) -> Coord {
+    s.parse().expect("valid coordinate")
+}
+
+// a game of a scenario, the other options as given
+pub fn game_with(scenario: &str, options: GameOptions) -> Game {
+    let scenario = scenario.parse::<Scenario>().expect("valid scenario");
+    Game::new(GameOptions { scenario: Some(scenario), ..options })
+}
+
+pub fn scenario_game(scenario: &str) -> Game {
+    game_with(scenario, Default::default())
+}
+
+// a game of SCENARIO
+pub fn game() -> Game {
+    scenario_game(SCENARIO)
+}
+
+pub fn health(game: &Game, at: &str) -> u8 {
+    game[coord(at)].unit().expect("unit").health()
+}
+
+// plays a coordinate pair (false if there is no legal action for it)
+pub fn play(game: &mut Game, from: &str, to: &str) -> bool {
+    game.play_turn_from_coords(coord(from), coord(to)).is_ok()
+}
+
+pub fn play_valid(game: &mut Game, from: &str, to: &str) {
+    game.play_turn_from_coords(coord(from), coord(to)).expect("valid action");
+}
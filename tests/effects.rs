@@ -0,0 +1,81 @@
+mod common;
+
+use ai_wargame::{Action, ActionOutcome, BoardCell, Game, GameOptions, RuleSet, UnitType};
+use common::{coord, game_with, health};
+
+const RULES : &str = "[effects]\ntype infect infect_damage stun shield\nVirus 2 1 0 0\nFirewall 0 0 0 2\nMortar 0 0 1 0";
+
+fn game(scenario: &str) -> Game {
+    let rules = RULES.parse::<RuleSet>().expect("valid rules");
+    game_with(scenario, GameOptions { rules, ..Default::default() })
+}
+
+fn attack(game: &mut Game, from: &str, to: &str) -> ActionOutcome {
+    game.play_turn_from_action(Action::Attack { from: coord(from), to: coord(to) }).expect("valid attack").2
+}
+
+#[test]
+fn infections_deal_damage_over_turns() {
+    let mut game = game("dim 5\nattacker AI E4\nattacker Virus C2\ndefender AI A0\ndefender Program B2");
+    let ActionOutcome::Damaged { to_target, effects, .. } = attack(&mut game, "C2", "B2") else { panic!("expected combat") };
+    assert_eq!(effects.target.infection, 2);
+    let health_after_hit = 9 - to_target;
+    assert_eq!(health(&game, "B2"), health_after_hit);
+    // infections hurt at the end of the turns of the unit's player
+    game.play_turn_from_action(Action::Pass).expect("pass");
+    assert_eq!(health(&game, "B2"), health_after_hit - 1);
+    assert_eq!(game[coord("B2")].to_string(), format!("dP{}iV1", health_after_hit - 1));
+    game.play_turn_from_action(Action::Pass).expect("pass");
+    game.play_turn_from_action(Action::Pass).expect("pass");
+    assert_eq!(health(&game, "B2"), health_after_hit - 2);
+    assert!(game[coord("B2")].unit().unwrap().effects().is_empty());
+}
+
+#[test]
+fn shields_absorb_the_next_hit_and_recharge() {
+    let mut game = game("dim 5\nattacker AI E4\nattacker Virus C2\ndefender AI A0\ndefender Firewall B2");
+    let ActionOutcome::Damaged { to_target, effects, .. } = attack(&mut game, "C2", "B2") else { panic!("expected combat") };
+    assert_eq!((to_target, effects.target.absorbed), (0, true));
+    assert_eq!(health(&game, "B2"), 9);
+    game.play_turn_from_action(Action::Pass).expect("pass");
+    let ActionOutcome::Damaged { to_target, effects, .. } = attack(&mut game, "C2", "B2") else { panic!("expected combat") };
+    assert!(to_target > 0 && !effects.target.absorbed);
+    game.play_turn_from_action(Action::Pass).expect("pass");
+    assert!(game[coord("B2")].unit().unwrap().has_shield(game.rules()));
+}
+
+#[test]
+fn stunned_units_cant_move() {
+    let mut game = game("dim 6\nattacker AI F5\nattacker Mortar E1\ndefender AI A0\ndefender Program B1");
+    let ActionOutcome::Damaged { effects, .. } = attack(&mut game, "E1", "B1") else { panic!("expected combat") };
+    assert_eq!(effects.target.stun, 1);
+    assert!(game[coord("B1")].unit().unwrap().is_stunned());
+    assert!(game.action_from_coords(coord("B1"), coord("B2")).is_err());
+    game.play_turn_from_action(Action::Pass).expect("pass");
+    game.play_turn_from_action(Action::Pass).expect("pass");
+    assert!(game.is_valid_move(coord("B1"), coord("B2")));
+}
+
+#[test]
+fn effects_are_in_rules_and_positions() {
+    let rules = RULES.parse::<RuleSet>().expect("valid rules");
+    assert_eq!((rules.infection(UnitType::Virus), rules.shield(UnitType::Firewall)), (2, 2));
+    assert_eq!(rules.to_string().parse::<RuleSet>().expect("valid rules"), rules);
+    assert!(!RuleSet::default().has_effects());
+    assert!("[effects]\ntype poison\nVirus 1".parse::<RuleSet>().is_err());
+    let cell = "dP3iV2s1r3".parse::<BoardCell>().expect("valid cell");
+    assert_eq!(cell.unit().unwrap().effects().infection(), Some((UnitType::Virus, 2)));
+    assert_eq!(cell.to_string(), "dP3iV2s1r3");
+    assert!("dP3x2".parse::<BoardCell>().is_err());
+}
+
+#[test]
+fn effects_of_positions_wear_off_without_effect_rules() {
+    let mut game = "D:1x4:aA9::dP3iV2:dA9".parse::<Game>().expect("valid position string");
+    assert!(!game.rules().has_effects());
+    game.play_turn_from_action(Action::Pass).expect("pass");
+    assert_eq!(game[coord("A2")].to_string(), "dP3iV1");
+    game.play_turn_from_action(Action::Pass).expect("pass");
+    game.play_turn_from_action(Action::Pass).expect("pass");
+    assert!(game[coord("A2")].unit().unwrap().effects().is_empty());
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use ai_wargame::{Action, Game, ParseActionError, UnitType, WargameError, game::IllegalActionReason};
+use common::{coord, game};
+
+fn rule_broken(game: &mut Game, action: Action) -> Option<IllegalActionReason> {
+    match game.perform_action(action) {
+        Err(WargameError::Rules(reason)) => Some(reason),
+        _ => None,
+    }
+}
+
+#[test]
+fn actions_fail_with_the_rule_they_break() {
+    let mut game = game();
+    assert_eq!(rule_broken(&mut game, Action::Attack { from: coord("C2"), to: coord("D2") }), Some(IllegalActionReason::FriendlyUnit));
+    assert_eq!(rule_broken(&mut game, Action::Repair { from: coord("D2"), to: coord("B2") }), Some(IllegalActionReason::OutOfRange));
+    assert_eq!(rule_broken(&mut game, Action::Repair { from: coord("C2"), to: coord("B2") }), Some(IllegalActionReason::OpposingUnit));
+    assert_eq!(rule_broken(&mut game, Action::Move { from: coord("C2"), to: coord("C4") }), Some(IllegalActionReason::OutOfRange));
+    assert_eq!(rule_broken(&mut game, Action::SelfDestruct { from: coord("C4") }), Some(IllegalActionReason::NoUnit));
+    assert_eq!(rule_broken(&mut game, Action::Spawn { at: coord("E3"), unit_type: UnitType::Virus }), Some(IllegalActionReason::CantSpawn));
+    assert_eq!(rule_broken(&mut game, Action::EndTurn), Some(IllegalActionReason::CantEndTurn));
+}
+
+#[test]
+fn failed_turns_keep_the_player() {
+    let mut game = game();
+    let error = game.play_turn_from_coords(coord("C2"), coord("C1")).expect_err("engaged unit");
+    assert!(matches!(error, WargameError::Rules(IllegalActionReason::Engaged)));
+    assert_eq!(game.total_moves(), 0);
+    assert!(game.play_turn_from_coords(coord("C2"), coord("B2")).is_ok());
+}
+
+#[test]
+fn parsing_errors_are_typed() {
+    let game = game();
+    assert!(matches!(game.parse_action("M C2C1"), Err(WargameError::Parse(ParseActionError::MissingSeparator('-')))));
+    assert!(matches!(game.parse_action("hello"), Err(WargameError::Parse(ParseActionError::UnknownKind(_)))));
+    assert!(matches!(game.parse_action("c2 c1"), Err(WargameError::Rules(IllegalActionReason::Engaged))));
+}
+
+#[test]
+fn replay_errors_carry_the_line() {
+    let error = Game::default().replay("M C4-B4\n\nA C0xC1\n").expect_err("attack on an empty cell");
+    match error {
+        WargameError::Record { line, error } => {
+            assert_eq!(line, 3);
+            assert!(matches!(*error, WargameError::Rules(IllegalActionReason::NoTarget)));
+        },
+        error => panic!("unexpected error: {error:?}"),
+    }
+}
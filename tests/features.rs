@@ -0,0 +1,53 @@
+mod common;
+
+use ai_wargame::{Game, GameOptions, Player, Scenario};
+use common::{game, SCENARIO};
+
+#[test]
+fn features_match_their_names() {
+    let game = game();
+    let names = Game::feature_names(game.players());
+    let features = game.features();
+    assert_eq!(names.len(), features.len());
+    let feature = |name: &str| features[names.iter().position(|n| n == name).unwrap_or_else(|| panic!("no feature {name}"))];
+    assert_eq!(feature("Attacker_Program_count"), 1.0);
+    assert_eq!(feature("Defender_Virus_count"), 1.0);
+    assert_eq!(feature("Attacker_health"), 27.0);
+    assert_eq!(feature("Defender_health"), 18.0);
+    // the defender Virus on B2 is 5 moves away from the attacker AI on E4
+    assert_eq!(feature("Defender_Virus0_ai_distance"), 5.0);
+    assert_eq!(feature("Defender_Virus1_ai_distance"), 0.0);
+    assert_eq!(feature("Attacker_Virus0_ai_distance"), 0.0);
+    // the Program on C2 and the Virus on B2 are engaged with each other
+    assert_eq!(feature("Attacker_engaged"), 1.0);
+    assert_eq!(feature("Defender_engaged"), 1.0);
+    assert_eq!(feature("Attacker_mobility"), game.mobility(Player::Attacker) as f32);
+    assert_eq!(feature("moves_remaining"), 100.0);
+    assert_eq!(feature("next_player"), 0.0);
+    assert_eq!(game.virus_distances(Player::Defender), vec![5]);
+    assert!(game.mobility(Player::Defender) > 0);
+}
+
+#[test]
+fn csv_rows_have_a_column_for_each_header() {
+    let scenario = SCENARIO.parse::<Scenario>().expect("valid scenario");
+    let options = GameOptions { scenario: Some(scenario), max_moves: Some(4), max_depth: Some(1), max_seconds: None, ..Default::default() };
+    let players = Game::new(options.clone()).players().to_vec();
+    let mut csv = Vec::new();
+    Game::self_play_features_csv(options.clone(), 2, &mut csv).expect("writes to a vector");
+    let csv = String::from_utf8(csv).expect("utf-8 csv");
+    let mut lines = csv.lines();
+    let header = lines.next().expect("header").split(',').collect::<Vec<_>>();
+    assert_eq!(header.len(), Game::feature_names(&players).len() + 3);
+    assert_eq!((header[0], header[1], header[header.len()-1]), ("game", "move", "result"));
+    let rows = lines.map(|line| line.split(',').count()).collect::<Vec<_>>();
+    // every position until the move cap, for both games
+    assert_eq!(rows.len(), 2 * 5);
+    assert!(rows.iter().all(|&columns| columns == header.len()));
+    // scenarios that don't fit the rules are reported instead of panicking
+    let rules = "[units]\ntype health\nProgram 5".parse().expect("valid rules");
+    let scenario = "dim 5\nattacker AI E4\nattacker Program D4 9\ndefender AI A0".parse::<Scenario>().expect("valid scenario");
+    let options = GameOptions { scenario: Some(scenario), rules, ..options };
+    let error = Game::self_play_features_csv(options, 1, &mut Vec::new()).expect_err("health too high");
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+}
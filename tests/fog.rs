@@ -0,0 +1,64 @@
+use ai_wargame::{Game, GameOptions, Player, Coord};
+
+fn fog_game(radius: i8) -> Game {
+    Game::new(GameOptions { fog_radius: Some(radius), max_depth: Some(2), max_seconds: Some(0.5), fog_samples: 4, ..Default::default() })
+}
+
+#[test]
+fn everything_is_visible_without_fog() {
+    let game = Game::default();
+    for player in Player::all() {
+        let observation = game.observation(player);
+        assert_eq!(observation.hidden_coords().count(), 0);
+    }
+}
+
+#[test]
+fn observations_only_show_nearby_units() {
+    let game = fog_game(1);
+    for player in Player::all() {
+        let observation = game.observation(player);
+        for coord in game.rect_iter() {
+            let near = game.player_coords(player).any(|own| own.is_in_range(coord, 1));
+            assert_eq!(observation.is_visible(coord), near, "{player} at {coord}");
+            match observation.cell(coord) {
+                Some(cell) => assert_eq!(cell, &game[coord]),
+                None => assert!(!near),
+            }
+        }
+        // the classic armies are out of sight of each other
+        assert!(game.player_coords(player.next()).all(|enemy| !observation.is_visible(enemy)));
+    }
+}
+
+#[test]
+fn determinized_games_match_the_observation() {
+    let game = fog_game(1);
+    let observer = game.player();
+    let observation = game.observation(observer);
+    let mut rng = rand::thread_rng();
+    for _ in 0..20 {
+        let sample = game.determinize(observer, &mut rng);
+        assert_eq!(sample.fog_radius(), None);
+        for coord in game.rect_iter().filter(|&c| observation.is_visible(c)) {
+            assert_eq!(sample[coord], game[coord], "visible cell {coord} changed");
+        }
+        for player in Player::all() {
+            for unit_type in ai_wargame::UnitType::all() {
+                assert_eq!(sample.unit_totals().count(player, unit_type), game.unit_totals().count(player, unit_type));
+            }
+        }
+        assert_eq!(sample.unit_totals(), &sample.recount_unit_totals());
+    }
+}
+
+#[test]
+fn determinized_search_suggests_a_legal_action() {
+    let mut game = fog_game(2);
+    let (_, action, _, _) = game.suggest_action();
+    let action = action.expect("an action is available");
+    let coords = action.into_coord_pair().expect("not a pass");
+    assert!(game.action_from_coords(coords.from, coords.to).is_ok(), "{action}");
+    assert!(game.play_turn_from_action(action).is_ok());
+    assert!(game.observation(Player::Attacker).is_visible(Coord::new(4, 4)));
+}
@@ -0,0 +1,118 @@
+use ai_wargame::{Coord, Game, GameOptions, Player, heuristics::{self, Heuristic, HEURISTIC_PRESETS, PHASE_MAX}};
+
+// hand-labelled positions: for the given player, each position is at least as good as the next one
+struct LabelledPositions {
+    name: &'static str,
+    player: Player,
+    positions: &'static [&'static str],
+}
+
+const LABELLED_POSITIONS : &[LabelledPositions] = &[
+    LabelledPositions {
+        name: "defender AI exposed after losing its guards",
+        player: Player::Attacker,
+        positions: &[
+            "A:dA9::::::dP9::::dF9::::aP9::::aF9:aV9:::aP9:aV9:aA9",
+            "A:dA9:dT9:dF9:::dT9:dP9::::dF9::::aP9::::aF9:aV9:::aP9:aV9:aA9",
+        ],
+    },
+    LabelledPositions {
+        name: "virus adjacent to the defender AI",
+        player: Player::Attacker,
+        positions: &[
+            "A:dA9:aV9::::dT9::dP9:::dF9:::::::::aF9:::aV9:aP9:aA9",
+            "A:dA9:::::dT9::dP9:::dF9:::::::aV9::aF9:::aV9:aP9:aA9",
+        ],
+    },
+    LabelledPositions {
+        name: "tech repair chain (AI and firewall)",
+        player: Player::Defender,
+        positions: &[
+            "D:dA9:dT9:dF9::::::::dP9::::::::aV9:aF9::::aP9:aA9",
+            "D:dA9:dT9:dF6::::::::dP9::::::::aV9:aF9::::aP9:aA9",
+            "D:dA6:dT9:dF6::::::::dP9::::::::aV9:aF9::::aP9:aA9",
+        ],
+    },
+];
+
+// returns a description of every pair of positions ranked in the wrong order by the heuristic
+// (from the labelled player's point of view and from the opponent's)
+fn ordering_violations(heuristic_name: &str, heuristic: &Heuristic) -> Vec<String> {
+    let mut violations = Vec::new();
+    for labelled in LABELLED_POSITIONS {
+        let games = labelled.positions.iter()
+            .map(|p| p.parse::<Game>().expect("valid position string"))
+            .collect::<Vec<_>>();
+        for (index, pair) in games.windows(2).enumerate() {
+            let (better, worse) = (&pair[0], &pair[1]);
+            for (player, expect_better) in [(labelled.player, true), (labelled.player.next(), false)] {
+                let (score_better, score_worse) = (heuristic(better, player), heuristic(worse, player));
+                if expect_better && score_better < score_worse || !expect_better && score_better > score_worse {
+                    violations.push(format!("{heuristic_name}: \"{}\" positions {} and {} for {player}: {score_better} vs {score_worse}",
+                        labelled.name, index, index+1));
+                }
+            }
+        }
+    }
+    violations
+}
+
+#[test]
+fn labelled_positions_round_trip() {
+    for labelled in LABELLED_POSITIONS {
+        for position in labelled.positions {
+            let game = position.parse::<Game>().expect("valid position string");
+            assert_eq!(game.to_string(), *position);
+        }
+    }
+}
+
+#[test]
+fn builtin_heuristics_respect_labelled_orderings() {
+    let violations = HEURISTIC_PRESETS.iter()
+        .filter(|preset| preset.name != "random")
+        .flat_map(|preset| ordering_violations(preset.name, &(preset.heuristic)()))
+        .collect::<Vec<_>>();
+    assert!(violations.is_empty(), "heuristic ordering violations:\n{}", violations.join("\n"));
+}
+
+// plays the first unit move of each turn (no material is lost)
+fn play_moves(game: &mut Game, moves: usize) {
+    for _ in 0..moves {
+        let action = game.possible_actions().find(|action| action.kind() == "move").expect("a unit can move");
+        game.play_turn_from_action(action).expect("legal action");
+    }
+}
+
+#[test]
+fn game_phase_follows_moves_and_material() {
+    let mut game = Game::new(GameOptions { max_moves: Some(4), ..Default::default() });
+    assert_eq!(heuristics::game_phase_value(&game), 0);
+    play_moves(&mut game, 2);
+    assert_eq!(heuristics::game_phase_value(&game), PHASE_MAX / 2);
+    play_moves(&mut game, 2);
+    assert_eq!(heuristics::game_phase_value(&game), PHASE_MAX);
+    // without a move cap, losing units moves the game forward
+    let mut game = Game::new(GameOptions { max_moves: None, ..Default::default() });
+    game.remove_cell(Coord::new(0, 2));
+    let phase = heuristics::game_phase_value(&game);
+    assert!(phase > 0 && phase < PHASE_MAX, "{phase}");
+}
+
+#[test]
+fn tapered_heuristics_blend_three_phases() {
+    let blend = heuristics::tapered(heuristics::constant_value(100), heuristics::constant_value(200), heuristics::constant_value(400));
+    let tapered = heuristics::tapered_attacker_heuristic();
+    let phases = [(0, 100, heuristics::opening_attacker_heuristic()), (2, 200, heuristics::default_attacker_heuristic()),
+        (4, 400, heuristics::endgame_attacker_heuristic())];
+    for (moves, value, weights) in phases {
+        let mut game = Game::new(GameOptions { max_moves: Some(4), ..Default::default() });
+        play_moves(&mut game, moves);
+        assert_eq!(blend(&game, Player::Attacker), value, "after {moves} moves");
+        assert_eq!(tapered(&game, Player::Attacker), weights(&game, Player::Attacker), "after {moves} moves");
+    }
+    // a quarter of the way: halfway between the opening and the middlegame
+    let mut game = Game::new(GameOptions { max_moves: Some(8), ..Default::default() });
+    play_moves(&mut game, 2);
+    assert_eq!(blend(&game, Player::Attacker), 150);
+}
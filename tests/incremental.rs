@@ -0,0 +1,33 @@
+use ai_wargame::{Game, GameOptions};
+use rand::seq::SliceRandom;
+
+fn play_random_game(options: GameOptions) {
+    let mut rng = rand::thread_rng();
+    let mut game = Game::new(options);
+    assert_eq!(*game.unit_totals(), game.recount_unit_totals());
+    while game.end_game_result().is_none() {
+        let actions = game.player_unit_coords(game.player())
+            .flat_map(|(coord,_)| game.possible_actions_from_coord(coord))
+            .collect::<Vec<_>>();
+        let Some(&action) = actions.choose(&mut rng) else {
+            break;
+        };
+        game.play_turn_from_action(action).expect("action should be valid");
+        assert_eq!(*game.unit_totals(), game.recount_unit_totals(), "after {action} in {game}");
+    }
+}
+
+#[test]
+fn incremental_totals_match_recount() {
+    for _ in 0..50 {
+        play_random_game(GameOptions::default());
+    }
+}
+
+#[test]
+fn incremental_totals_match_recount_without_mutual_damage() {
+    let options = GameOptions { mutual_damage: false, ..Default::default() };
+    for _ in 0..20 {
+        play_random_game(options.clone());
+    }
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use ai_wargame::{Action, Game, game::IllegalActionReason};
+use common::{coord, scenario_game, SCENARIO};
+
+// a second attacker Program that can only move
+fn game() -> Game {
+    scenario_game(&format!("{SCENARIO}\nattacker Program E2"))
+}
+
+fn reason(game: &Game, from: &str, to: &str) -> Option<IllegalActionReason> {
+    game.action_from_coords(coord(from), coord(to)).err()
+}
+
+#[test]
+fn moves_explain_why_they_are_invalid() {
+    let game = game();
+    assert_eq!(reason(&game, "C2", "C1"), Some(IllegalActionReason::Engaged));
+    assert_eq!(reason(&game, "E2", "E3"), Some(IllegalActionReason::ForwardOnly));
+    assert_eq!(reason(&game, "C2", "A2"), Some(IllegalActionReason::OutOfRange));
+    assert_eq!(reason(&game, "B2", "B1"), Some(IllegalActionReason::WrongPlayer));
+    assert_eq!(reason(&game, "C4", "C3"), Some(IllegalActionReason::NoUnit));
+    assert_eq!(game.move_legality(coord("E2"), coord("D2")), Err(IllegalActionReason::Blocked));
+    assert_eq!(game.move_legality(coord("E2"), coord("E1")), Ok(()));
+}
+
+#[test]
+fn repairs_explain_why_they_are_invalid() {
+    let game = game();
+    assert_eq!(reason(&game, "D2", "C2"), Some(IllegalActionReason::FullHealth));
+    assert_eq!(reason(&game, "E2", "D2"), Some(IllegalActionReason::CantRepair));
+}
+
+#[test]
+fn explicit_actions_explain_a_mismatch() {
+    let game = game();
+    let (c2, c1, b2, d2) = (coord("C2"), coord("C1"), coord("B2"), coord("D2"));
+    assert_eq!(game.action_legality(Action::Attack { from: c2, to: c1 }), Err(IllegalActionReason::NoTarget));
+    assert_eq!(game.action_legality(Action::Repair { from: c2, to: b2 }), Err(IllegalActionReason::OpposingUnit));
+    assert_eq!(game.action_legality(Action::Attack { from: d2, to: c2 }), Err(IllegalActionReason::FriendlyUnit));
+    assert_eq!(game.action_legality(Action::Attack { from: c2, to: b2 }), Ok(()));
+    assert_eq!(game.action_legality(Action::EndTurn), Err(IllegalActionReason::CantEndTurn));
+}
+
+#[test]
+fn reasons_are_shown_to_players() {
+    let mut game = game();
+    let played = game.human_play_turn_from_coords(None::<&mut std::io::Sink>, coord("C2"), coord("C1")).expect("no io errors");
+    assert_eq!(played, Err(IllegalActionReason::Engaged));
+    assert_eq!(IllegalActionReason::Engaged.to_string(), "the unit is engaged in combat and can't move");
+    let reason = game.action_from_coords(coord("E2"), coord("E3")).expect_err("illegal move");
+    assert!(reason.to_string().contains("can only move forward"));
+    assert!(game.action_from_coords(coord("E2"), coord("E1")).is_ok());
+}
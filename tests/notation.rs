@@ -0,0 +1,59 @@
+mod common;
+
+use ai_wargame::{Action, Game, ParseActionError, UnitType};
+use common::coord;
+
+#[test]
+fn actions_round_trip_through_the_notation() {
+    let actions = [
+        Action::Move { from: coord("E3"), to: coord("D3") },
+        Action::Attack { from: coord("E3"), to: coord("D3") },
+        Action::Repair { from: coord("A1"), to: coord("B1") },
+        Action::SelfDestruct { from: coord("C2") },
+        Action::Spawn { at: coord("C3"), unit_type: UnitType::Virus },
+        Action::Pass,
+        Action::EndTurn,
+    ];
+    let notation = actions.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(notation, ["M E3-D3", "A E3xD3", "R A1+B1", "S C2!", "N C3=Virus", "pass", "end"]);
+    for (action, text) in actions.iter().zip(&notation) {
+        assert_eq!(text.parse::<Action>().as_ref(), Ok(action));
+    }
+    assert_eq!("a E3 x D3".parse(), Ok(Action::Attack { from: coord("E3"), to: coord("D3") }));
+    // rows past Z are lowercase letters, x included
+    assert_eq!("A x3xD3".parse(), Ok(Action::Attack { from: coord("x3"), to: coord("D3") }));
+}
+
+#[test]
+fn malformed_actions_give_typed_errors() {
+    assert_eq!("".parse::<Action>(), Err(ParseActionError::Empty));
+    assert_eq!("Q E3-D3".parse::<Action>(), Err(ParseActionError::UnknownKind("Q".to_string())));
+    assert_eq!("M E3D3".parse::<Action>(), Err(ParseActionError::MissingSeparator('-')));
+    assert_eq!("S C2".parse::<Action>(), Err(ParseActionError::MissingSeparator('!')));
+    assert_eq!("R A1+9".parse::<Action>(), Err(ParseActionError::InvalidCoord("9".to_string())));
+    assert_eq!("N C3=Dragon".parse::<Action>(), Err(ParseActionError::UnknownUnitType("Dragon".to_string())));
+    assert_eq!("pass now".parse::<Action>(), Err(ParseActionError::TrailingInput("now".to_string())));
+}
+
+#[test]
+fn explicit_actions_do_not_infer_intent() {
+    let game = Game::default();
+    // the classic setup has an attacker Program at D3 and an empty C3
+    let inferred = game.parse_action("d3 c3").expect("coordinate pair");
+    assert_eq!(inferred, Action::Move { from: coord("D3"), to: coord("C3") });
+    assert_eq!(game.parse_action("m d3-c3").ok(), Some(inferred));
+    let attack = game.parse_action("A D3xC3").expect("well formed");
+    assert!(!game.is_legal_action(attack));
+    assert!(game.parse_action("M D3C3").is_err());
+}
+
+#[test]
+fn records_replay_both_forms() {
+    let mut game = Game::default();
+    let played = game.replay("# opening\nM C4-B4\nc0 c1  # coordinate pair\n\nb4 b3\n").expect("legal record");
+    assert_eq!(played.len(), 3);
+    assert_eq!(played[1].1, Action::Move { from: coord("C0"), to: coord("C1") });
+    assert_eq!(game.total_moves(), 3);
+    let error = Game::default().replay("M C4-B4\nA C0xC1\n").expect_err("illegal attack");
+    assert!(error.to_string().starts_with("line 2:"));
+}
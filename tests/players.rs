@@ -0,0 +1,69 @@
+mod common;
+
+use ai_wargame::{Action, Coord, Game, GameOptions, Player, Scenario};
+use common::{game_with, play};
+
+fn game(scenario: &str, teams: &str) -> Game {
+    let teams = if teams.is_empty() { Default::default() } else { teams.parse().expect("valid teams") };
+    game_with(scenario, GameOptions { teams, max_depth: Some(3), max_seconds: None, ..Default::default() })
+}
+
+#[test]
+fn classic_setup_puts_each_army_in_its_corner() {
+    let players = Player::all().collect::<Vec<_>>();
+    let scenario = Scenario::classic_for(8, 8, &players);
+    assert_eq!(Scenario::bundled("four-corners").map(|s| s.units), Some(scenario.units.clone()));
+    let game = Game::new(GameOptions { players, rows: 8, cols: 8, ..Default::default() });
+    assert_eq!(game.players().len(), 4);
+    for (player, corner) in [(Player::Attacker, "H7"), (Player::Defender, "A0"), (Player::Raider, "A7"), (Player::Invader, "H0")] {
+        assert_eq!(game.unit_totals().total_count(player), 6);
+        assert_eq!(game.unit_totals().ai_coord(player), Some(corner.parse().unwrap()), "{player}");
+    }
+    assert_eq!(Game::default().players(), [Player::Attacker, Player::Defender]);
+}
+
+#[test]
+fn turns_skip_eliminated_players() {
+    let mut game = game("dim 6\nplayers attacker defender raider\nattacker AI F5\nattacker Virus A4\ndefender AI A0\nraider AI A5 1\nraider Program B5", "");
+    assert!(play(&mut game, "A4", "A5"));
+    // the raider lost its AI so its remaining units leave the board
+    assert!(game.is_eliminated(Player::Raider));
+    assert_eq!(game.unit_totals().total_count(Player::Raider), 0);
+    assert_eq!(game.player(), Player::Defender);
+    assert_eq!(game.end_game_result(), None);
+    assert!(play(&mut game, "A0", "B0"));
+    assert_eq!(game.player(), Player::Attacker);
+}
+
+#[test]
+fn players_move_forward_from_their_corner() {
+    let mut game = game("dim 6\nplayers attacker defender raider\nfirst raider\nattacker AI F5\ndefender AI A0\nraider AI A5\nraider Program B4", "");
+    assert!(!play(&mut game, "B4", "A4"));
+    assert!(play(&mut game, "B4", "B3"));
+}
+
+#[test]
+fn allies_share_victory_and_do_not_fight() {
+    let scenario = "dim 6\nplayers attacker defender raider\nfirst raider\nattacker AI F5\nattacker Virus B1\ndefender AI A1 1\nraider AI A5\nraider Virus A2";
+    let mut game = game(scenario, "attacker+raider");
+    assert!(game.are_allies(Player::Attacker, Player::Raider));
+    assert!(matches!(game.action_from_coords(Coord::new(0,2), Coord::new(0,1)), Ok(Action::Attack { .. })));
+    assert!(play(&mut game, "A2", "A1"));
+    // the winner stands for the whole team
+    let winner = game.end_game_result().and_then(|result| result.winner()).expect("a winner");
+    assert!(game.are_allies(winner, Player::Raider));
+    let mut game = self::game("dim 6\nplayers attacker defender raider\nattacker AI F5\nattacker Virus A4\ndefender AI A0\nraider AI A5", "attacker+raider");
+    assert!(game.action_from_coords(Coord::new(0,4), Coord::new(0,5)).is_err());
+    assert!(!play(&mut game, "A4", "A5"));
+}
+
+#[test]
+fn search_plays_multi_player_games() {
+    let players = Player::all().take(3).collect::<Vec<_>>();
+    let options = GameOptions { players, rows: 8, cols: 8, max_depth: Some(2), max_seconds: None, ..Default::default() };
+    let mut game = Game::new(options);
+    for player in [Player::Attacker, Player::Defender, Player::Raider, Player::Attacker] {
+        assert_eq!(game.player(), player);
+        assert!(game.computer_play_turn(None::<&mut std::io::Sink>).unwrap().is_some());
+    }
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use ai_wargame::{Action, Coord, Game, GameOptions, RuleSet, UnitType};
+use common::{coord, game_with, health};
+
+fn game(scenario: &str, rules: RuleSet) -> Game {
+    game_with(scenario, GameOptions { rules, ..Default::default() })
+}
+
+#[test]
+fn mortars_attack_from_a_distance_without_retaliation() {
+    let mut game = game("dim 6\nattacker AI F5\nattacker Mortar E1\ndefender AI A0\ndefender Program B1", Default::default());
+    assert_eq!(game.action_from_coords(coord("E1"), coord("B1")).ok(), Some(Action::Attack { from: coord("E1"), to: coord("B1") }));
+    game.play_turn_from_coords(coord("E1"), coord("B1")).expect("ranged attack");
+    assert_eq!(health(&game, "B1"), 7);
+    assert_eq!(health(&game, "E1"), 9);
+    // out of range, and off the lines
+    assert!(game.action_from_coords(coord("B1"), coord("E1")).is_err());
+    let game = self::game("dim 6\nattacker AI F5\nattacker Mortar E1\ndefender AI A0\ndefender Program C2", Default::default());
+    assert!(game.action_from_coords(coord("E1"), coord("C2")).is_err());
+    assert!(game.action_from_coords(coord("E1"), coord("A0")).is_err());
+}
+
+#[test]
+fn mortars_fire_diagonally_over_units_but_not_walls() {
+    let scenario = "dim 6\nattacker AI F5\nattacker Mortar E1\nattacker Firewall D2\ndefender AI A0\ndefender Program C3\ndefender Tech B1";
+    let game = game(scenario, Default::default());
+    assert!(game.is_in_attack_range(coord("E1"), coord("C3")));
+    let game = self::game(&format!("{scenario}\nwall C1 D1"), Default::default());
+    assert!(game.is_in_attack_range(coord("E1"), coord("C3")));
+    assert!(!game.is_in_attack_range(coord("E1"), coord("B1")));
+    let rules = "[units]\ntype line_of_sight diagonal\nMortar units no".parse::<RuleSet>().expect("valid rules");
+    let game = self::game(&scenario.replace("D2", "D1"), rules);
+    assert!(!game.is_in_attack_range(coord("E1"), coord("C3")));
+    assert!(!game.is_in_attack_range(coord("E1"), coord("B1")));
+    assert!(game.is_in_attack_range(coord("E1"), coord("E4")));
+}
+
+#[test]
+fn adjacent_attacks_keep_mutual_damage() {
+    let mut game = game("dim 5\nattacker AI E4\nattacker Mortar B1\ndefender AI A0\ndefender Program A1", Default::default());
+    assert!(game.possible_actions_from_coord(coord("B1")).any(|action| action == Action::Attack { from: coord("B1"), to: coord("A0") }));
+    game.play_turn_from_coords(coord("B1"), coord("A1")).expect("melee attack");
+    assert_eq!(health(&game, "A1"), 7);
+    assert_eq!(health(&game, "B1"), 6);
+    // melee units reach one cell, without diagonals
+    assert!(!game.is_in_attack_range(coord("A0"), coord("B1")));
+}
+
+#[test]
+fn ranges_are_in_the_rules() {
+    let rules = RuleSet::default();
+    assert_eq!(rules.range(UnitType::Mortar), 3);
+    assert_eq!(rules.range(UnitType::Virus), 1);
+    assert_eq!(rules.to_string().parse::<RuleSet>().expect("valid rules"), rules);
+    let table = UnitType::damage_table(&rules, None, ToString::to_string, ToString::to_string);
+    assert_eq!(table[0].last().map(String::as_str), Some("range"));
+    assert_eq!(table.last().and_then(|row| row.last()).map(String::as_str), Some("3+diag"));
+    assert!("[units]\ntype range\nMortar 0".parse::<RuleSet>().is_err());
+    let error = "[units]\ntype range\nMortar 127".parse::<RuleSet>().expect_err("range too long");
+    assert_eq!(error.to_string(), "line 3: invalid range (1 to 126): 127");
+}
+
+#[test]
+fn long_ranges_stop_at_the_board_edges() {
+    let rules = "[units]\ntype range\nMortar 126".parse::<RuleSet>().expect("valid rules");
+    let game = game("dim 6\nattacker AI F5\nattacker Mortar E1\ndefender AI A0\ndefender Program B1", rules);
+    assert!(game.possible_actions_from_coord(coord("E1")).any(|action| action == Action::Attack { from: coord("E1"), to: coord("B1") }));
+    // nothing to scan far from the board
+    assert_eq!(game.possible_actions_from_coord(Coord::new(126, 126)).count(), 0);
+}
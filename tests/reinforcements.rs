@@ -0,0 +1,60 @@
+mod common;
+
+use ai_wargame::{Action, ActionOutcome, Game, GameOptions, Player, UnitType, game::Reinforcements};
+use common::{coord, game_with};
+
+fn game(reinforcements: Option<&str>) -> Game {
+    let reinforcements = reinforcements.map(|r| r.parse::<Reinforcements>().expect("valid reinforcements"));
+    game_with("dim 5\nattacker AI E3\nattacker Virus D3\ndefender AI A0\ndefender Tech A1",
+        GameOptions { reinforcements, max_depth: Some(3), max_seconds: None, ..Default::default() })
+}
+
+fn spawn(at: &str, unit_type: UnitType) -> Action {
+    Action::Spawn { at: coord(at), unit_type }
+}
+
+#[test]
+fn ais_spawn_units_next_to_them() {
+    let mut game = game(Some("2"));
+    assert!(!game.is_legal_action(spawn("C3", UnitType::Program)));
+    assert!(!game.is_legal_action(spawn("E4", UnitType::AI)));
+    let (_, _, outcome) = game.play_turn_from_action(spawn("E4", UnitType::Program)).expect("valid spawn");
+    assert!(matches!(outcome, ActionOutcome::Spawned { unit_type: UnitType::Program }));
+    let unit = game[coord("E4")].unit().expect("spawned unit");
+    assert_eq!((unit.unit_type(), unit.health()), (UnitType::Program, 9));
+    assert_eq!(game.unit_totals().count(Player::Attacker, UnitType::Program), 1);
+    assert_eq!(game.reserve(Player::Attacker).spawns, 1);
+}
+
+#[test]
+fn spawns_are_limited_by_the_budget_and_cooldown() {
+    let mut game = game(Some("2:2"));
+    game.play_turn_from_action(spawn("E4", UnitType::Firewall)).expect("valid spawn");
+    game.play_turn_from_action(Action::Pass).expect("pass");
+    assert!(!game.is_legal_action(spawn("E2", UnitType::Firewall)));
+    game.play_turn_from_action(Action::Pass).expect("pass");
+    game.play_turn_from_action(Action::Pass).expect("pass");
+    game.play_turn_from_action(spawn("E2", UnitType::Firewall)).expect("valid spawn after the cooldown");
+    game.play_turn_from_action(Action::Pass).expect("pass");
+    assert_eq!(game.reserve(game.player()).spawns, 0);
+    assert!(!game.possible_actions().any(|action| matches!(action, Action::Spawn { .. })));
+}
+
+#[test]
+fn spawns_are_possible_actions_only_with_reinforcements() {
+    assert!(!game(None).possible_actions().any(|action| matches!(action, Action::Spawn { .. })));
+    let mut game = game(Some("1"));
+    // E2 and E4 next to the AI (D3 holds the Virus), with every type but the AI
+    let spawns = game.possible_actions().filter(|action| matches!(action, Action::Spawn { .. })).count();
+    assert_eq!(spawns, 2 * (UnitType::cardinality() - 1));
+    assert!(game.computer_play_turn(None::<&mut std::io::Sink>).expect("no io errors").is_some());
+}
+
+#[test]
+fn spawns_are_parsed_and_displayed() {
+    let action = Game::parse_spawn("spawn virus c3").expect("valid spawn");
+    assert_eq!(action, spawn("C3", UnitType::Virus));
+    assert_eq!(action.to_string(), "N C3=Virus");
+    assert!(Game::parse_spawn("spawn dragon C3").is_none());
+    assert!("0".parse::<Reinforcements>().is_ok() && "2:0".parse::<Reinforcements>().is_err());
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use ai_wargame::{Game, GameOptions, GameResult, Player, game::RepetitionRule};
+use common::{game_with, play_valid as play};
+
+const SHUFFLE : [(&str, &str); 4] = [("C2", "C3"), ("A2", "A3"), ("C3", "C2"), ("A3", "A2")];
+
+fn shuffle_game(repetition: RepetitionRule) -> Game {
+    game_with("dim 5\nattacker AI E4\nattacker Virus C2\ndefender AI A0 5\ndefender Tech A2\ndefender Tech B0",
+        GameOptions { repetition, ..Default::default() })
+}
+
+#[test]
+fn threefold_repetition_is_a_draw() {
+    let mut game = shuffle_game("draw".parse().unwrap());
+    assert_eq!(game.repetitions(), 1);
+    for round in 1..=2 {
+        assert_eq!(game.end_game_result(), None);
+        for (from, to) in SHUFFLE {
+            play(&mut game, from, to);
+        }
+        assert_eq!(game.repetitions(), round + 1);
+    }
+    assert_eq!(game.end_game_result(), Some(GameResult::Draw));
+}
+
+#[test]
+fn repetition_can_be_a_defender_win() {
+    let mut game = shuffle_game(RepetitionRule::DefenderWins { count: 2 });
+    for (from, to) in SHUFFLE {
+        play(&mut game, from, to);
+    }
+    assert_eq!(game.end_game_result(), Some(GameResult::Winner(Player::Defender)));
+}
+
+#[test]
+fn repetitions_are_ignored_when_off() {
+    let mut game = shuffle_game(RepetitionRule::Off);
+    for _ in 0..3 {
+        for (from, to) in SHUFFLE {
+            play(&mut game, from, to);
+        }
+    }
+    assert_eq!(game.repetitions(), 4);
+    assert_eq!(game.end_game_result(), None);
+}
+
+#[test]
+fn non_move_actions_reset_the_history() {
+    let mut game = shuffle_game("draw:2".parse().unwrap());
+    play(&mut game, "C2", "C3");
+    // repairing the AI changes the position for good
+    play(&mut game, "B0", "A0");
+    play(&mut game, "C3", "C2");
+    assert_eq!(game.repetitions(), 1);
+    assert_eq!(game.end_game_result(), None);
+}
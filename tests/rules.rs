@@ -0,0 +1,42 @@
+mod common;
+
+use ai_wargame::{Action, GameOptions, RuleSet, UnitType};
+use common::{coord, game_with, health};
+
+#[test]
+fn rules_round_trip_through_the_text_format() {
+    let rules = RuleSet::default();
+    assert_eq!(rules.to_string().parse::<RuleSet>().expect("valid rules"), rules);
+    let custom = "[units]\ntype health range\nProgram 5 2\n\n[damage]\nfrom AI\nVirus 2 # weaker viruses\n".parse::<RuleSet>().expect("valid rules");
+    assert_ne!(custom, rules);
+    assert_eq!((custom.initial_health(UnitType::Program), custom.range(UnitType::Program)), (5, 2));
+    assert_eq!(custom.damage_amount(UnitType::Virus, UnitType::AI), 2);
+    // anything not specified keeps its built-in value
+    assert_eq!(custom.damage_amount(UnitType::Virus, UnitType::Tech), rules.damage_amount(UnitType::Virus, UnitType::Tech));
+    assert_eq!(custom.to_string().parse::<RuleSet>().expect("valid rules"), custom);
+}
+
+#[test]
+fn rule_errors_carry_the_line() {
+    let error = |text: &str| text.parse::<RuleSet>().expect_err("invalid rules").to_string();
+    assert_eq!(error("[units]\ntype health\n\n[speed]\ntype AI\nVirus 1"), "line 6: unknown section: speed");
+    assert_eq!(error("[damage]\nfrom AI\nTrojan 3"), "line 3: unknown unit type: Trojan");
+    assert_eq!(error("[damage]\nfrom Worm\nVirus 3"), "line 3: unknown unit type: worm");
+    assert_eq!(error("# health\n[units]\ntype health\nAI 0"), "line 4: invalid health (1 to 9): 0");
+    assert_eq!(error("[units]\ntype health\nAI 12"), "line 3: invalid health (1 to 9): 12");
+    assert_eq!(error("[units]\ntype health\nAI nine"), "line 3: invalid health (1 to 9): nine");
+    assert_eq!(error("[units]\ntype health range\nAI 9"), "line 3: expected 2 values");
+}
+
+#[test]
+fn games_play_with_the_loaded_rules() {
+    let scenario = "dim 5\nattacker AI E4\nattacker Virus B0\ndefender AI A0";
+    let rules = "[damage]\nfrom AI\nVirus 2".parse::<RuleSet>().expect("valid rules");
+    let mut game = game_with(scenario, GameOptions { rules, ..Default::default() });
+    game.play_turn_from_action(Action::Attack { from: coord("B0"), to: coord("A0") }).expect("valid attack");
+    assert_eq!(health(&game, "A0"), 7);
+    // the built-in Virus destroys the AI in one attack
+    let mut game = game_with(scenario, Default::default());
+    game.play_turn_from_action(Action::Attack { from: coord("B0"), to: coord("A0") }).expect("valid attack");
+    assert!(game[coord("A0")].is_empty());
+}
@@ -0,0 +1,60 @@
+use ai_wargame::{Game, GameOptions, RuleSet, Scenario, WargameError, scenario::BUNDLED_SCENARIOS};
+
+#[test]
+fn bundled_classic_matches_default_setup() {
+    assert_eq!(Scenario::bundled("classic"), Some(Scenario::classic(5, 5)));
+    let options = GameOptions { scenario: Scenario::bundled("classic"), ..Default::default() };
+    assert_eq!(Game::new(options).to_string(), Game::default().to_string());
+}
+
+#[test]
+fn bundled_scenarios_round_trip() {
+    for (name, _) in BUNDLED_SCENARIOS {
+        let scenario = Scenario::bundled(name).expect("bundled scenario");
+        assert_eq!(scenario.to_string().parse::<Scenario>().expect("valid scenario"), scenario, "{name}");
+        let options = GameOptions { scenario: Some(scenario.clone()), ..Default::default() };
+        let game = Game::new(options);
+        assert_eq!((game.rows(), game.cols()), (scenario.rows, scenario.cols), "{name}");
+        assert_eq!(game.player(), scenario.first_player, "{name}");
+        let units = ai_wargame::Player::all().map(|p| game.unit_totals().total_count(p)).sum::<usize>();
+        assert_eq!(units, scenario.units.len(), "{name}");
+    }
+}
+
+#[test]
+fn invalid_scenarios_are_rejected() {
+    for text in [
+        "attacker AI A0",
+        "dim 5\nattacker AI E5",
+        "dim 5\nattacker AI A0\ndefender AI A0",
+        "dim 5\nattacker AI A0 10",
+        "dim 5\nattacker Tank A0",
+    ] {
+        assert!(text.parse::<Scenario>().is_err(), "{text:?}");
+    }
+}
+
+#[test]
+fn games_check_the_scenario_against_the_rules() {
+    // a full health Program is too strong for rules where Programs start weaker
+    let scenario = "dim 5\nattacker AI E4\nattacker Program D4 9\ndefender AI A0".parse::<Scenario>().expect("valid scenario");
+    let rules = "[units]\ntype health\nProgram 5".parse::<RuleSet>().expect("valid rules");
+    let options = GameOptions { scenario: Some(scenario.clone()), rules, ..Default::default() };
+    let error = Game::try_new(options).expect_err("invalid scenario for the rules");
+    assert!(matches!(&error, WargameError::Scenario(_)), "{error:?}");
+    assert_eq!(error.to_string(), "health of Program at D4 must be between 1 and 5");
+    assert!(Game::try_new(GameOptions { scenario: Some(scenario), ..Default::default() }).is_ok());
+}
+
+#[test]
+fn rectangular_positions_round_trip() {
+    let options = GameOptions { scenario: Scenario::bundled("corridor"), ..Default::default() };
+    let game = Game::new(options);
+    assert_eq!((game.rows(), game.cols()), (4, 9));
+    let position = game.to_string();
+    assert!(position.starts_with("A:4x9:"), "{position}");
+    let parsed = position.parse::<Game>().expect("valid position string");
+    assert_eq!((parsed.rows(), parsed.cols()), (4, 9));
+    assert_eq!(parsed.to_string(), position);
+    assert!("A:4x9:dA9".parse::<Game>().is_err());
+}
@@ -0,0 +1,114 @@
+#![cfg(feature="server")]
+
+mod common;
+
+use ai_wargame::{GameOptions, Player, server::GameServer};
+use common::{game_with, SCENARIO};
+use serde_json::{json, Value};
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+
+fn start(humans: Vec<Player>) -> SocketAddr {
+    let game = game_with(SCENARIO, GameOptions { max_depth: Some(2), max_seconds: Some(0.5), debug: false, ..Default::default() });
+    let listener = TcpListener::bind("127.0.0.1:0").expect("free local port");
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || GameServer::new(game, humans).serve(listener));
+    addr
+}
+
+// status and body of a request to the local server
+fn request(addr: SocketAddr, method: &str, path: &str, body: Option<Value>) -> (u16, String) {
+    let mut stream = TcpStream::connect(addr).expect("server listening");
+    let body = body.map(|body| body.to_string()).unwrap_or_default();
+    write!(stream, "{method} {path} HTTP/1.1\r\nHost: {addr}\r\nContent-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").expect("complete response");
+    let status = head.split_whitespace().nth(1).and_then(|status| status.parse().ok()).expect("status code");
+    (status, body.to_string())
+}
+
+fn api(addr: SocketAddr, method: &str, path: &str, body: Option<Value>) -> (u16, Value) {
+    let (status, body) = request(addr, method, path, body);
+    (status, serde_json::from_str(&body).expect("json reply"))
+}
+
+#[test]
+fn serves_the_page_and_the_state() {
+    let addr = start(vec![Player::Attacker]);
+    let (status, page) = request(addr, "GET", "/", None);
+    assert_eq!(status, 200);
+    assert!(page.contains("/api/state") && page.contains("cellClick"));
+    let (status, reply) = api(addr, "GET", "/api/state", None);
+    assert_eq!(status, 200);
+    assert_eq!(reply["success"], true);
+    assert_eq!(reply["data"]["player"], "Attacker");
+    assert_eq!(reply["data"]["human_turn"], true);
+    assert!(reply["data"]["board"].as_str().unwrap().contains("cellClick(2,2)"));
+    assert!(reply["data"]["damage_table"].as_str().unwrap().contains("stats-table"));
+}
+
+#[test]
+fn humans_play_legal_actions_only() {
+    let addr = start(vec![Player::Attacker]);
+    let (_, reply) = api(addr, "GET", "/api/actions?row=2&col=2", None);
+    let actions = reply["data"].as_array().unwrap();
+    assert!(actions.iter().any(|action| action["action"] == "A C2xB2" && action["kind"] == "attack" && action["to"] == json!({ "row": 1, "col": 2 })));
+    let (status, reply) = api(addr, "POST", "/api/play", Some(json!({ "from": { "row": 2, "col": 2 }, "to": { "row": 2, "col": 1 } })));
+    assert_eq!(status, 400);
+    assert_eq!(reply["error"], "Invalid move: the unit is engaged in combat and can't move");
+    let (status, reply) = api(addr, "POST", "/api/play", Some(json!({ "action": "A C2xB2" })));
+    assert_eq!(status, 200);
+    assert_eq!(reply["data"]["action"]["action"], "A C2xB2");
+    assert_eq!(reply["data"]["state"]["player"], "Defender");
+    assert_eq!(reply["data"]["log"][0], "Attacker: A C2xB2");
+}
+
+#[test]
+fn cells_off_the_board_are_rejected() {
+    let addr = start(vec![Player::Attacker]);
+    for query in ["row=127&col=0", "row=0&col=-1", "row=5&col=5"] {
+        let (status, reply) = api(addr, "GET", &format!("/api/actions?{query}"), None);
+        assert_eq!((status, reply["success"].clone()), (400, json!(false)), "{query}");
+    }
+    // an idle client doesn't keep the others waiting
+    let _idle = TcpStream::connect(addr).expect("server listening");
+    let (status, _) = api(addr, "GET", "/api/state", None);
+    assert_eq!(status, 200);
+}
+
+#[test]
+fn computer_plays_against_a_human() {
+    let addr = start(vec![Player::Attacker]);
+    let (_, reply) = api(addr, "GET", "/api/suggest", None);
+    assert!(reply["data"]["action"]["action"].is_string());
+    api(addr, "POST", "/api/play", Some(json!({ "action": "pass" })));
+    let (status, reply) = api(addr, "POST", "/api/ai", None);
+    assert_eq!(status, 200);
+    assert!(reply["data"]["log"][0].as_str().unwrap().starts_with("Defender: "));
+    assert_eq!(reply["data"]["state"]["player"], "Attacker");
+    let (_, reply) = api(addr, "POST", "/api/reset", None);
+    assert_eq!(reply["data"]["moves"], 0);
+    assert_eq!(reply["data"]["log"], json!([]));
+}
+
+#[test]
+fn computer_plays_both_sides_to_the_end() {
+    let addr = start(Vec::new());
+    let mut moves = 0;
+    loop {
+        let (status, reply) = api(addr, "POST", "/api/ai", None);
+        if status == 409 {
+            assert_eq!(reply["error"], "the game is over");
+            break;
+        }
+        assert!(reply["data"]["state"]["board"].is_string());
+        moves += 1;
+        assert!(moves <= 100, "the game should end at the move cap");
+    }
+    let (_, reply) = api(addr, "GET", "/api/state", None);
+    assert!(reply["data"]["result"].as_str().unwrap().contains("in"));
+    assert_eq!(request(addr, "GET", "/api/play", None).0, 405);
+    assert_eq!(request(addr, "GET", "/nothing", None).0, 404);
+}
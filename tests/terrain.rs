@@ -0,0 +1,58 @@
+mod common;
+
+use ai_wargame::{Action, ActionOutcome, Game, Scenario, Terrain};
+use common::{coord, health, scenario_game as game_from_scenario};
+
+#[test]
+fn walls_are_impassable() {
+    let game = game_from_scenario("dim 5\nwall B2\nattacker AI E4\nattacker Virus C2\ndefender AI A0");
+    assert_eq!(game.terrain(coord("B2")), Terrain::Wall);
+    assert!(!game.is_valid_move(coord("C2"), coord("B2")));
+    assert!(game.action_from_coords(coord("C2"), coord("B2")).is_err());
+    assert!("dim 5\nwall B2\nattacker Virus B2".parse::<Scenario>().is_err());
+}
+
+#[test]
+fn fortified_reduces_incoming_damage() {
+    let mut game = game_from_scenario("dim 5\nfortified B2\nattacker AI E4\nattacker Virus C2\ndefender AI A0\ndefender Tech B2");
+    let reduction = game.rules().damage_reduction(Terrain::Fortified);
+    let damage = game.rules().damage_amount(ai_wargame::UnitType::Virus, ai_wargame::UnitType::Tech);
+    let outcome = game.perform_action(Action::Attack { from: coord("C2"), to: coord("B2") }).expect("valid attack");
+    assert!(matches!(outcome, ActionOutcome::Damaged { to_source, to_target, .. } if to_source == damage && to_target == damage - reduction));
+    assert_eq!(health(&game, "B2"), 9 - (damage - reduction));
+    assert_eq!(health(&game, "C2"), 9 - damage);
+}
+
+#[test]
+fn depots_repair_at_the_start_of_the_owners_turn() {
+    let mut game = game_from_scenario("dim 5\nfirst defender\ndepot C2\nattacker AI E4\nattacker Virus C2 5\ndefender AI A0");
+    let repair = game.rules().terrain_repair(Terrain::Depot);
+    assert_eq!(health(&game, "C2"), 5);
+    game.next_turn();
+    assert_eq!(health(&game, "C2"), 5 + repair);
+    game.next_turn();
+    assert_eq!(health(&game, "C2"), 5 + repair);
+    assert_eq!(game.unit_totals(), &game.recount_unit_totals());
+}
+
+#[test]
+fn chokepoints_pin_engaged_units() {
+    let text = "dim 5\nattacker AI E4\nattacker Virus C2\ndefender AI A0\ndefender Program B2";
+    let game = game_from_scenario(text);
+    assert!(game.is_valid_move(coord("C2"), coord("C1")));
+    let game = game_from_scenario(&format!("{text}\nchokepoint C2"));
+    assert!(!game.is_valid_move(coord("C2"), coord("C1")));
+}
+
+#[test]
+fn terrain_survives_moves_and_position_strings() {
+    let mut game = game_from_scenario("dim 5\nfortified C1\nwall A4\nattacker AI E4\nattacker Virus C2\ndefender AI A0");
+    game.perform_action(Action::Move { from: coord("C2"), to: coord("C1") }).expect("valid move");
+    game.perform_action(Action::Move { from: coord("C1"), to: coord("C0") }).expect("valid move");
+    assert_eq!(game.terrain(coord("C1")), Terrain::Fortified);
+    let position = game.to_string();
+    let parsed = position.parse::<Game>().expect("valid position string");
+    assert_eq!(parsed.to_string(), position);
+    assert_eq!(parsed.terrain(coord("A4")), Terrain::Wall);
+    assert_eq!(parsed.terrain(coord("C1")), Terrain::Fortified);
+}
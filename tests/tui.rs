@@ -0,0 +1,83 @@
+#![cfg(feature="tui")]
+
+mod common;
+
+use ai_wargame::{Action, Game, Player, game::tui::{tui_input, TuiInput, TuiState}};
+use common::{coord, game};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{Terminal, backend::TestBackend, style::Modifier};
+
+fn press(game: &Game, state: &mut TuiState, codes: &[KeyCode]) -> Option<Action> {
+    codes.iter().fold(None, |_, &code| {
+        let input = tui_input(KeyEvent::from(code)).expect("bound key");
+        game.tui_handle_input(state, input)
+    })
+}
+
+fn render(game: &Game, state: &TuiState) -> ratatui::buffer::Buffer {
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).expect("test terminal");
+    terminal.draw(|frame| game.tui_draw(frame, state, None)).expect("draw");
+    terminal.backend().buffer().clone()
+}
+
+fn screen_text(buffer: &ratatui::buffer::Buffer) -> String {
+    buffer.content().iter().map(|cell| cell.symbol()).collect()
+}
+
+#[test]
+fn arrow_keys_select_a_unit_and_its_target() {
+    let game = game();
+    let mut state = TuiState::new(&game);
+    assert_eq!(state.cursor, coord("C2"));
+    assert_eq!(press(&game, &mut state, &[KeyCode::Enter]), None);
+    assert_eq!(state.selected, Some(coord("C2")));
+    assert_eq!(game.tui_targets(&state), game.possible_actions_from_coord(coord("C2")).collect::<Vec<_>>());
+    let action = press(&game, &mut state, &[KeyCode::Up, KeyCode::Enter]);
+    assert_eq!(action, Some(Action::Attack { from: coord("C2"), to: coord("B2") }));
+    assert_eq!(state.selected, None);
+}
+
+#[test]
+fn invalid_selections_explain_why() {
+    let game = game();
+    let mut state = TuiState::new(&game);
+    press(&game, &mut state, &[KeyCode::Up, KeyCode::Enter]);
+    assert_eq!(state.selected, None);
+    assert_eq!(state.message.as_deref(), Some("Invalid move: the unit belongs to another player!"));
+    // the cursor stays on the board
+    press(&game, &mut state, &[KeyCode::Up, KeyCode::Up, KeyCode::Up, KeyCode::Left, KeyCode::Left, KeyCode::Left]);
+    assert_eq!(state.cursor, coord("A0"));
+}
+
+#[test]
+fn screen_shows_board_highlights_and_log() {
+    let mut game = game();
+    let mut state = TuiState::new(&game);
+    state.selected = Some(coord("C2"));
+    let buffer = render(&game, &state);
+    let text = screen_text(&buffer);
+    assert!(text.contains("Attacker to play"));
+    assert!(text.contains("moves played"));
+    // the cursor cell is reversed, the attack target highlighted
+    let reversed = buffer.content().iter().filter(|cell| cell.modifier.contains(Modifier::REVERSED)).count();
+    assert_eq!(reversed, 3);
+    assert!(buffer.content().iter().any(|cell| cell.bg == ratatui::style::Color::Red));
+    let mut output = Vec::new();
+    game.human_play_turn_from_action(Some(&mut output), Action::Attack { from: coord("C2"), to: coord("B2") }).expect("no io errors").expect("legal attack");
+    state.log_output(&output);
+    assert_eq!(state.log.first().map(String::as_str), Some("Attacker: A C2xB2"));
+    assert_eq!(game.player(), Player::Defender);
+    assert!(screen_text(&render(&game, &state)).contains("Attacker: A C2xB2"));
+}
+
+#[test]
+fn side_panel_toggles_the_tables() {
+    let game = game();
+    let mut state = TuiState::new(&game);
+    assert!(!screen_text(&render(&game, &state)).contains("Damage"));
+    press(&game, &mut state, &[KeyCode::Char('t')]);
+    assert!(state.show_tables);
+    let text = screen_text(&render(&game, &state));
+    assert!(text.contains("Damage") && text.contains("Repair") && text.contains("from / to"));
+    assert_eq!(tui_input(KeyEvent::from(KeyCode::Char('q'))), Some(TuiInput::Quit));
+}
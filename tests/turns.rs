@@ -0,0 +1,62 @@
+mod common;
+
+use ai_wargame::{Action, Coord, Game, GameOptions, Player, game::ActionPoints};
+use common::{game_with, play};
+
+fn game(scenario: &str, action_points: Option<ActionPoints>) -> Game {
+    game_with(scenario, GameOptions { action_points, max_depth: Some(3), max_seconds: None, ..Default::default() })
+}
+
+const SCENARIO : &str = "dim 5\nattacker AI E4\nattacker Program E2\nattacker Virus D4\ndefender AI A0\ndefender Tech A1";
+
+#[test]
+fn turns_have_several_actions() {
+    let mut game = game(SCENARIO, Some(ActionPoints::PerTurn(2)));
+    assert_eq!(game.actions_left(), Some(2));
+    assert!(play(&mut game, "E2", "D2"));
+    assert_eq!((game.player(), game.total_moves(), game.actions_left()), (Player::Attacker, 0, Some(1)));
+    assert!(play(&mut game, "D2", "C2"));
+    assert_eq!((game.player(), game.total_moves(), game.actions_left()), (Player::Defender, 1, Some(2)));
+}
+
+#[test]
+fn turns_end_early_after_an_action() {
+    let mut game = game(SCENARIO, Some(ActionPoints::PerTurn(3)));
+    assert!(!game.is_legal_action(Action::EndTurn));
+    assert!(game.play_turn_from_action(Action::EndTurn).is_err());
+    assert!(play(&mut game, "D4", "C4"));
+    assert!(game.possible_actions().any(|action| action == Action::EndTurn));
+    game.play_turn_from_action(Action::EndTurn).expect("end of turn");
+    assert_eq!((game.player(), game.turn_phase().actions()), (Player::Defender, 0));
+    // there is no end of turn with a single action per turn
+    let game = self::game(SCENARIO, None);
+    assert!(!game.possible_actions().any(|action| action == Action::EndTurn));
+}
+
+#[test]
+fn units_have_their_own_budget() {
+    let mut game = game(SCENARIO, Some(ActionPoints::PerUnit(1)));
+    assert_eq!(game.actions_left(), None);
+    assert!(play(&mut game, "E2", "D2"));
+    // the program moved with its activation
+    assert!(!game.can_activate(Coord::new(3, 2)));
+    assert!(!play(&mut game, "D2", "C2"));
+    assert!(play(&mut game, "D4", "C4"));
+    assert_eq!(game.player(), Player::Attacker);
+    // the turn ends once no unit can act
+    assert!(play(&mut game, "E4", "E3"));
+    assert_eq!(game.player(), Player::Defender);
+}
+
+#[test]
+fn search_plays_sub_moves() {
+    let mut game = game(SCENARIO, Some(ActionPoints::PerTurn(2)));
+    let mut actions = 0;
+    while game.player() == Player::Attacker {
+        game.computer_play_turn(None::<&mut std::io::Sink>).expect("no io errors").expect("an action");
+        actions += 1;
+    }
+    assert!((1..=2).contains(&actions));
+    assert!("turn:0".parse::<ActionPoints>().is_err());
+    assert_eq!("unit:2".parse::<ActionPoints>().unwrap(), ActionPoints::PerUnit(2));
+}
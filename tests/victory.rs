@@ -0,0 +1,63 @@
+mod common;
+
+use ai_wargame::{Coord, Game, GameOptions, GameResult, Player, game::VictoryConditions};
+use common::{game_with, play_valid as play};
+use ai_wargame::game::victory::{Elimination, Hill, MoveCapRule, parse_tie_break};
+
+fn game(scenario: &str, max_moves: Option<usize>, victory: VictoryConditions) -> Game {
+    game_with(scenario, GameOptions { max_moves, victory, ..Default::default() })
+}
+
+const STRONGER_ATTACKER : &str = "dim 5\nattacker AI E4\nattacker Virus C2\nattacker Program D4\ndefender AI A0 5\ndefender Tech A2";
+
+#[test]
+fn move_cap_goes_to_the_defender_by_default() {
+    let mut game = game(STRONGER_ATTACKER, Some(1), Default::default());
+    play(&mut game, "D4", "D3");
+    assert_eq!(game.end_game_result(), Some(GameResult::Winner(Player::Defender)));
+}
+
+#[test]
+fn move_cap_can_compare_material() {
+    let victory = VictoryConditions { move_cap: MoveCapRule::Material, ..Default::default() };
+    let mut game = game(STRONGER_ATTACKER, Some(1), victory);
+    play(&mut game, "D4", "D3");
+    assert_eq!(game.end_game_result(), Some(GameResult::Winner(Player::Attacker)));
+    // equal material falls back to the tie-break
+    let victory = VictoryConditions { move_cap: MoveCapRule::Material, tie_break: GameResult::Draw, ..Default::default() };
+    let mut game = self::game("dim 5\nattacker AI E4\ndefender AI A0", Some(1), victory);
+    play(&mut game, "E4", "E3");
+    assert_eq!(game.end_game_result(), Some(GameResult::Draw));
+}
+
+#[test]
+fn all_units_elimination_keeps_playing_without_an_ai() {
+    let scenario = "dim 5\nattacker AI E4\nattacker Virus B1\ndefender AI A1 1\ndefender Tech A4";
+    let mut game = self::game(scenario, None, Default::default());
+    play(&mut game, "B1", "A1");
+    assert_eq!(game.end_game_result(), Some(GameResult::Winner(Player::Attacker)));
+    let victory = VictoryConditions { elimination: Elimination::AllUnits, ..Default::default() };
+    let mut game = self::game(scenario, None, victory);
+    play(&mut game, "B1", "A1");
+    assert!(!game.is_eliminated(Player::Defender));
+    assert_eq!(game.end_game_result(), None);
+}
+
+#[test]
+fn holding_the_hill_wins() {
+    let victory = VictoryConditions { hill: Some("C2".parse().unwrap()), ..Default::default() };
+    let mut game = game("dim 5\nattacker AI E4\nattacker Program D2\ndefender AI A0\ndefender Tech A4", None, victory);
+    assert_eq!(game.end_game_result(), None);
+    play(&mut game, "D2", "C2");
+    assert_eq!(game.end_game_result(), None);
+    play(&mut game, "A4", "B4");
+    assert_eq!(game.end_game_result(), Some(GameResult::Winner(Player::Attacker)));
+}
+
+#[test]
+fn tie_breaks_parse() {
+    assert_eq!(parse_tie_break("draw").unwrap(), GameResult::Draw);
+    assert_eq!(parse_tie_break("attacker").unwrap(), GameResult::Winner(Player::Attacker));
+    assert!(parse_tie_break("nobody").is_err());
+    assert!("B1-D3".parse::<Hill>().unwrap().contains(Coord::new(2, 2)));
+}